@@ -0,0 +1,137 @@
+/// An error that occurred while compiling a regex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileError {
+    /// `{n,m}` repetition whose maximum is smaller than its minimum.
+    InvalidRepetition { min: usize, max: usize },
+    /// The same name is used by more than one named capturing group.
+    DuplicateGroupName(String),
+    /// Back reference to a group index that doesn't exist.
+    UnknownGroup(usize),
+    /// Back reference to a group name that doesn't exist.
+    UnknownGroupName(String),
+    /// Back reference inside look-around, which is not supported.
+    BackRefInLookAround,
+    /// Back reference with an engine other than the backtracker, which is not supported.
+    BackRefNotSupported,
+    /// Look-around, back reference or leftmost-longest match kind in a regex given to `StreamMatcher`,
+    /// which is not supported.
+    NotStreamable,
+    /// The compiled program would exceed the configured size limit, in number of instructions.
+    SizeLimitExceeded { size: usize, limit: usize },
+}
+
+impl std::fmt::Display for CompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompileError::InvalidRepetition { min, max } => write!(
+                f,
+                "invalid repetition {{{},{}}}: maximum is smaller than minimum",
+                min, max
+            ),
+            CompileError::DuplicateGroupName(name) => {
+                write!(f, "duplicate capture group name: {}", name)
+            }
+            CompileError::UnknownGroup(index) => {
+                write!(f, "back reference to unknown capture group: {}", index)
+            }
+            CompileError::UnknownGroupName(name) => {
+                write!(f, "back reference to unknown capture group name: {}", name)
+            }
+            CompileError::BackRefInLookAround => {
+                write!(f, "back reference inside look-around is not supported")
+            }
+            CompileError::BackRefNotSupported => {
+                write!(
+                    f,
+                    "back reference is only supported by the backtracking engine"
+                )
+            }
+            CompileError::NotStreamable => write!(
+                f,
+                "look-around, back reference and leftmost-longest match are not supported by the stream matcher"
+            ),
+            CompileError::SizeLimitExceeded { size, limit } => write!(
+                f,
+                "compiled regex exceeds size limit: {} instructions (limit {})",
+                size, limit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// An error that occurred while parsing a pattern by `Regex::parse`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    position: usize,
+    kind: ParseErrorKind,
+}
+
+/// The kind of `ParseError`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The pattern ended where more was expected.
+    UnexpectedEnd,
+    /// A character which is not allowed at the position.
+    UnexpectedChar(char),
+    /// `[name]` refers to a predicate not registered.
+    UnknownPredicate(String),
+    /// A number too large for repetition or back reference.
+    InvalidNumber(String),
+    /// A back reference, while no equality function is set in the registry.
+    BackRefWithoutEq,
+}
+
+impl ParseError {
+    pub(crate) fn new(position: usize, kind: ParseErrorKind) -> Self {
+        Self { position, kind }
+    }
+
+    /// Returns the byte offset in the pattern where the error occurred.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the kind of the error.
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of pattern")?,
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c)?,
+            ParseErrorKind::UnknownPredicate(name) => write!(f, "unknown predicate: {}", name)?,
+            ParseErrorKind::InvalidNumber(digits) => write!(f, "invalid number: {}", digits)?,
+            ParseErrorKind::BackRefWithoutEq => {
+                write!(f, "back reference without equality function")?
+            }
+        }
+
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// An error that occurred while searching with a compiled regex.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchError {
+    /// The backtracker followed more paths than the limit set by `CompileOptions::backtrack_limit`.
+    BacktrackLimitExceeded { limit: usize },
+}
+
+impl std::fmt::Display for MatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchError::BacktrackLimitExceeded { limit } => {
+                write!(f, "backtrack limit exceeded: {} paths", limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MatchError {}
@@ -1,8 +1,19 @@
+mod error;
 mod regex;
+mod replacer;
 
-use std::{collections::HashMap, ops::Range, rc::Rc};
+use std::{collections::HashMap, ops::Range, sync::Arc};
 
-pub use regex::Regex;
+pub use error::{CompileError, MatchError, ParseError, ParseErrorKind};
+pub use regex::{
+    vm::{
+        Anchored, CompileOptions, CompiledRegexInVm, Engine, IterMatches, MatchEvent, MatchKind,
+        MatchTrace, PredicateTest, RegexSet, SetMatches, StreamMatcher, TraceStep, TraceThread,
+        DEFAULT_BACKTRACK_LIMIT, DEFAULT_SIZE_LIMIT,
+    },
+    EqFn, PairFn, PredicateRegistry, Regex,
+};
+pub use replacer::{Replacer, Template};
 
 #[cfg(not(target_env = "msvc"))]
 use tikv_jemallocator::Jemalloc;
@@ -56,19 +67,22 @@ struct CaptureLocation {
 #[derive(Debug)]
 pub struct Captures<'t, I> {
     input: &'t [I],
-    capture_locations: Vec<CaptureLocation>,
-    named_capture_index: Rc<HashMap<String, usize>>,
+    capture_locations: Vec<Option<CaptureLocation>>,
+    named_capture_index: Arc<HashMap<String, usize>>,
 }
 
 impl<'t, I> Captures<'t, I> {
     /// Returns the match associated with the capture group at index i.
     /// If i does not correspond to a capture group, or if the capture group did not participate in the match, then None is returned.
     pub fn get(&self, index: usize) -> Option<Match<'t, I>> {
-        self.capture_locations.get(index).map(|location| Match {
-            input: self.input,
-            start: location.start,
-            end: location.end,
-        })
+        self.capture_locations
+            .get(index)
+            .and_then(|location| location.as_ref())
+            .map(|location| Match {
+                input: self.input,
+                start: location.start,
+                end: location.end,
+            })
     }
 
     /// Returns the match for the capture group named name. If name isn’t a valid capture group or didn’t match anything, then None is returned.
@@ -88,7 +102,15 @@ impl<'t, I> Captures<'t, I> {
 
 pub trait CompiledRegex<I> {
     /// Returns true if and only if there is a match for the regex in the slice given.
-    fn is_match(&self, input: &[I]) -> bool;
+    fn is_match(&self, input: &[I]) -> bool {
+        self.is_match_at(input, 0)
+    }
+
+    /// Returns the same as is_match, but starts the search at the given offset.
+    ///
+    /// The significance of the starting point is that it takes the surrounding context into consideration.
+    /// For example, the `^` anchor only matches at offset `0` of the slice, not at `start`.
+    fn is_match_at(&self, input: &[I], start: usize) -> bool;
 
     /// Returns true if and only if match the entire input slice.
     fn is_full_match(&self, input: &[I]) -> bool {
@@ -100,9 +122,294 @@ pub trait CompiledRegex<I> {
     }
 
     /// Returns the start and end range of the leftmost-first match in slice. If no match exists, then None is returned.
-    fn find<'t>(&self, input: &'t [I]) -> Option<Match<'t, I>>;
+    fn find<'t>(&self, input: &'t [I]) -> Option<Match<'t, I>> {
+        self.find_at(input, 0)
+    }
+
+    /// Returns the same as find, but starts the search at the given offset.
+    ///
+    /// The significance of the starting point is that it takes the surrounding context into consideration.
+    /// For example, the `^` anchor only matches at offset `0` of the slice, not at `start`.
+    /// Returned offsets are always relative to the whole slice.
+    fn find_at<'t>(&self, input: &'t [I], start: usize) -> Option<Match<'t, I>>;
+
+    /// Returns an iterator for each successive non-overlapping match in slice.
+    fn find_iter<'r, 't>(&'r self, input: &'t [I]) -> Matches<'r, 't, Self, I>
+    where
+        Self: Sized,
+    {
+        Matches {
+            regex: self,
+            input,
+            last_end: 0,
+            last_match: None,
+        }
+    }
+
+    /// Returns an iterator over the leftmost-first match at each start position in slice, including overlapping ones.
+    ///
+    /// After a match, the search resumes at the next element from its start, instead of its end.
+    fn find_overlapping_iter<'r, 't>(
+        &'r self,
+        input: &'t [I],
+    ) -> OverlappingMatches<'r, 't, Self, I>
+    where
+        Self: Sized,
+    {
+        OverlappingMatches {
+            regex: self,
+            input,
+            next_start: 0,
+        }
+    }
 
     /// Returns the capture groups corresponding to the leftmost-first match in text.
     /// Capture group 0 always corresponds to the entire match. If no match is found, then None is returned.
-    fn captures<'t>(&self, input: &'t [I]) -> Option<Captures<'t, I>>;
+    fn captures<'t>(&self, input: &'t [I]) -> Option<Captures<'t, I>> {
+        self.captures_at(input, 0)
+    }
+
+    /// Returns the same as captures, but starts the search at the given offset.
+    ///
+    /// The significance of the starting point is that it takes the surrounding context into consideration.
+    /// For example, the `^` anchor only matches at offset `0` of the slice, not at `start`.
+    /// Captured offsets are always relative to the whole slice.
+    fn captures_at<'t>(&self, input: &'t [I], start: usize) -> Option<Captures<'t, I>>;
+
+    /// Returns an iterator over all the non-overlapping capture groups matched in slice.
+    fn captures_iter<'r, 't>(&'r self, input: &'t [I]) -> CaptureMatches<'r, 't, Self, I>
+    where
+        Self: Sized,
+    {
+        CaptureMatches {
+            regex: self,
+            input,
+            last_end: 0,
+            last_match: None,
+        }
+    }
+
+    /// Returns an iterator of sub-slices of the input delimited by a match of the regex.
+    /// Namely, each element of the iterator corresponds to a part of the input that isn't matched by the regex.
+    fn split<'r, 't>(&'r self, input: &'t [I]) -> Split<'r, 't, Self, I>
+    where
+        Self: Sized,
+    {
+        Split {
+            finder: self.find_iter(input),
+            last: 0,
+        }
+    }
+
+    /// Returns an iterator of at most `limit` sub-slices of the input delimited by a match of the regex.
+    /// The last sub-slice yielded contains the remainder of the input.
+    fn splitn<'r, 't>(&'r self, input: &'t [I], limit: usize) -> SplitN<'r, 't, Self, I>
+    where
+        Self: Sized,
+    {
+        SplitN {
+            splits: self.split(input),
+            n: limit,
+        }
+    }
+
+    /// Returns a new vector with the leftmost-first match replaced by the replacement provided.
+    /// If no match is found, then a copy of the input is returned.
+    ///
+    /// The replacement can be a closure `FnMut(&Captures<I>) -> impl IntoIterator<Item = I>` or a [`Template`].
+    fn replace<R: Replacer<I>>(&self, input: &[I], rep: R) -> Vec<I>
+    where
+        Self: Sized,
+        I: Clone,
+    {
+        self.replacen(input, 1, rep)
+    }
+
+    /// Returns a new vector with all non-overlapping matches replaced by the replacement provided.
+    /// This is the same as calling replacen with limit set to 0.
+    fn replace_all<R: Replacer<I>>(&self, input: &[I], rep: R) -> Vec<I>
+    where
+        Self: Sized,
+        I: Clone,
+    {
+        self.replacen(input, 0, rep)
+    }
+
+    /// Returns a new vector with at most `limit` non-overlapping matches replaced by the replacement provided.
+    /// If `limit` is 0, then all non-overlapping matches are replaced.
+    fn replacen<R: Replacer<I>>(&self, input: &[I], limit: usize, mut rep: R) -> Vec<I>
+    where
+        Self: Sized,
+        I: Clone,
+    {
+        let mut replaced = Vec::with_capacity(input.len());
+        let mut last_match = 0;
+        for (i, caps) in self.captures_iter(input).enumerate() {
+            if limit > 0 && i >= limit {
+                break;
+            }
+            let whole_match = caps.get(0).expect("Unexpected missing 0th capture.");
+            replaced.extend_from_slice(&input[last_match..whole_match.start()]);
+            rep.replace_append(&caps, &mut replaced);
+            last_match = whole_match.end();
+        }
+        replaced.extend_from_slice(&input[last_match..]);
+
+        replaced
+    }
+}
+
+/// An iterator over all non-overlapping matches for a particular slice.
+///
+/// An empty match immediately following a previous match is skipped, and the search resumes at the next element.
+///
+/// `'r` is the lifetime of the compiled regex and `'t` is the lifetime of the matched slice.
+pub struct Matches<'r, 't, R: ?Sized, I> {
+    regex: &'r R,
+    input: &'t [I],
+    last_end: usize,
+    last_match: Option<usize>,
+}
+
+impl<'r, 't, R: CompiledRegex<I> + ?Sized, I> Iterator for Matches<'r, 't, R, I> {
+    type Item = Match<'t, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.last_end <= self.input.len() {
+            let found_match = self.regex.find_at(self.input, self.last_end)?;
+            if found_match.start() == found_match.end() {
+                // Advance one element to make progress on empty match.
+                self.last_end = found_match.end() + 1;
+                if Some(found_match.end()) == self.last_match {
+                    continue;
+                }
+            } else {
+                self.last_end = found_match.end();
+            }
+            self.last_match = Some(found_match.end());
+            return Some(found_match);
+        }
+
+        None
+    }
+}
+
+/// An iterator over the leftmost-first match at each start position, including overlapping ones.
+///
+/// `'r` is the lifetime of the compiled regex and `'t` is the lifetime of the matched slice.
+pub struct OverlappingMatches<'r, 't, R: ?Sized, I> {
+    regex: &'r R,
+    input: &'t [I],
+    next_start: usize,
+}
+
+impl<'r, 't, R: CompiledRegex<I> + ?Sized, I> Iterator for OverlappingMatches<'r, 't, R, I> {
+    type Item = Match<'t, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_start > self.input.len() {
+            return None;
+        }
+        let found_match = self.regex.find_at(self.input, self.next_start)?;
+        self.next_start = found_match.start() + 1;
+
+        Some(found_match)
+    }
+}
+
+/// An iterator that yields all non-overlapping capture groups matching a particular regex.
+///
+/// `'r` is the lifetime of the compiled regex and `'t` is the lifetime of the matched slice.
+pub struct CaptureMatches<'r, 't, R: ?Sized, I> {
+    regex: &'r R,
+    input: &'t [I],
+    last_end: usize,
+    last_match: Option<usize>,
+}
+
+impl<'r, 't, R: CompiledRegex<I> + ?Sized, I> Iterator for CaptureMatches<'r, 't, R, I> {
+    type Item = Captures<'t, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.last_end <= self.input.len() {
+            let captures = self.regex.captures_at(self.input, self.last_end)?;
+            let whole_match = captures.get(0).expect("Unexpected missing 0th capture.");
+            if whole_match.start() == whole_match.end() {
+                // Advance one element to make progress on empty match.
+                self.last_end = whole_match.end() + 1;
+                if Some(whole_match.end()) == self.last_match {
+                    continue;
+                }
+            } else {
+                self.last_end = whole_match.end();
+            }
+            self.last_match = Some(whole_match.end());
+            return Some(captures);
+        }
+
+        None
+    }
+}
+
+/// An iterator of sub-slices of the input delimited by matches of a regex.
+///
+/// `'r` is the lifetime of the compiled regex and `'t` is the lifetime of the split slice.
+pub struct Split<'r, 't, R: ?Sized, I> {
+    finder: Matches<'r, 't, R, I>,
+    last: usize,
+}
+
+impl<'r, 't, R: CompiledRegex<I> + ?Sized, I> Iterator for Split<'r, 't, R, I> {
+    type Item = &'t [I];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let input = self.finder.input;
+        match self.finder.next() {
+            Some(found_match) => {
+                let piece = &input[self.last..found_match.start()];
+                self.last = found_match.end();
+                Some(piece)
+            }
+            None => {
+                if self.last > input.len() {
+                    None
+                } else {
+                    let piece = &input[self.last..];
+                    self.last = input.len() + 1;
+                    Some(piece)
+                }
+            }
+        }
+    }
+}
+
+/// An iterator of at most `N` sub-slices of the input delimited by matches of a regex.
+///
+/// `'r` is the lifetime of the compiled regex and `'t` is the lifetime of the split slice.
+pub struct SplitN<'r, 't, R: ?Sized, I> {
+    splits: Split<'r, 't, R, I>,
+    n: usize,
+}
+
+impl<'r, 't, R: CompiledRegex<I> + ?Sized, I> Iterator for SplitN<'r, 't, R, I> {
+    type Item = &'t [I];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.n == 0 {
+            return None;
+        }
+        self.n -= 1;
+        if self.n > 0 {
+            return self.splits.next();
+        }
+
+        let input = self.splits.finder.input;
+        if self.splits.last > input.len() {
+            None
+        } else {
+            let piece = &input[self.splits.last..];
+            self.splits.last = input.len() + 1;
+            Some(piece)
+        }
+    }
 }
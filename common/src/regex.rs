@@ -1,9 +1,17 @@
+mod parser;
 pub mod vm;
 
-use std::rc::Rc;
+use std::sync::Arc;
 
-use self::vm::CompiledRegexInVm;
-use super::CompiledRegex;
+pub use self::parser::PredicateRegistry;
+use self::vm::{CompileOptions, CompiledRegexInVm};
+use crate::{CompileError, ParseError};
+
+/// Function that compares a captured value with an input value, used by back reference.
+pub type EqFn<T> = Arc<dyn Fn(&T, &T) -> bool + Send + Sync>;
+
+/// Predicate on the previous value and the current value.
+pub type PairFn<T> = Arc<dyn Fn(&T, &T) -> bool + Send + Sync>;
 
 #[derive(Clone)]
 pub enum Regex<T> {
@@ -11,30 +19,47 @@ pub enum Regex<T> {
     Begin,
     /// Like a '$' in ragex. Regex that matches the end of the input.
     End,
-    /// Like a `[character class]` in regex. Regex that matches any values that satisfy the given predicate.
-    Satisfy(Rc<dyn Fn(&T) -> bool>),
-    /// Like a `[^character class]` in regex. Regex that matches any values that not satisfy the given predicate.
-    NotSatisfy(Rc<dyn Fn(&T) -> bool>),
+    /// Like a `[character class]` in regex. Regex that matches any values that satisfy the given predicate,
+    /// with an optional label of the predicate.
+    Satisfy(Arc<dyn Fn(&T) -> bool + Send + Sync>, Option<String>),
+    /// Like a `[^character class]` in regex. Regex that matches any values that not satisfy the given predicate,
+    /// with an optional label of the predicate. The function is already negated.
+    NotSatisfy(Arc<dyn Fn(&T) -> bool + Send + Sync>, Option<String>),
+    /// Regex that matches a value which satisfies the given predicate together with the previous value,
+    /// with an optional label of the predicate. It never matches the first value of the input.
+    SatisfyPair(PairFn<T>, Option<String>),
     /// Like a `RS` in regex. Concatenate two regex.
-    Concat(Rc<Regex<T>>, Rc<Regex<T>>),
+    Concat(Arc<Regex<T>>, Arc<Regex<T>>),
     /// Like a `(R)` in regex. Numbered capturing group (submatch).
-    Group(Rc<Regex<T>>),
+    Group(Arc<Regex<T>>),
     /// Like a `(?<name>R)` in regex. Numbered capturing group (submatch).
-    NamedGroup(String, Rc<Regex<T>>),
+    NamedGroup(String, Arc<Regex<T>>),
     /// Like a `(?:R)` in regex. Numbered non-capturing group.
-    NonCapturingGroup(Rc<Regex<T>>),
+    NonCapturingGroup(Arc<Regex<T>>),
     /// Like a `R|S` in regex. Regex alternation.
-    Or(Rc<Regex<T>>, Rc<Regex<T>>),
+    Or(Arc<Regex<T>>, Arc<Regex<T>>),
     /// Like a `?`, `??` in regex. Regex zero or one.
-    ZeroOrOne(Rc<Regex<T>>, bool),
+    ZeroOrOne(Arc<Regex<T>>, bool),
     /// Like a `*`, `*?` in regex. Regex zero or one.
-    Repeat0(Rc<Regex<T>>, bool),
+    Repeat0(Arc<Regex<T>>, bool),
     /// Like a `+`, `+?` in regex. Regex one or more.
-    Repeat1(Rc<Regex<T>>, bool),
+    Repeat1(Arc<Regex<T>>, bool),
     /// Like a `{n}` in regex. Exactly N-times.
-    RepeatN(Rc<Regex<T>>, usize),
+    RepeatN(Arc<Regex<T>>, usize),
     /// Like a `{n,m}`, `{n,m}?` or `{n,}`, `{n,}?` in regex. n or n+1 or .. m times.
-    RepeatMinMax(Rc<Regex<T>>, usize, Option<usize>, bool),
+    RepeatMinMax(Arc<Regex<T>>, usize, Option<usize>, bool),
+    /// Like a `(?=R)` in regex. Regex that asserts R matches at the current position without consuming input.
+    LookAhead(Arc<Regex<T>>),
+    /// Like a `(?!R)` in regex. Regex that asserts R does not match at the current position.
+    NegativeLookAhead(Arc<Regex<T>>),
+    /// Like a `(?<=R)` in regex. Regex that asserts R matches ending at the current position.
+    LookBehind(Arc<Regex<T>>),
+    /// Like a `(?<!R)` in regex. Regex that asserts R does not match ending at the current position.
+    NegativeLookBehind(Arc<Regex<T>>),
+    /// Like a `\1` in regex. Regex that matches the sub-slice captured by the numbered group, compared by given function.
+    BackRef(usize, EqFn<T>),
+    /// Like a `\k<name>` in regex. Regex that matches the sub-slice captured by the named group, compared by given function.
+    NamedBackRef(String, EqFn<T>),
 }
 
 impl<T> std::fmt::Debug for Regex<T> {
@@ -42,8 +67,18 @@ impl<T> std::fmt::Debug for Regex<T> {
         match self {
             Regex::Begin => f.write_str("Begin"),
             Regex::End => f.write_str("End"),
-            Regex::Satisfy(_) => f.debug_tuple("Satisfy").field(&"<fn>").finish(),
-            Regex::NotSatisfy(_) => f.debug_tuple("NotSatisfy").field(&"<fn>").finish(),
+            Regex::Satisfy(_, label) => f
+                .debug_tuple("Satisfy")
+                .field(&label.as_deref().unwrap_or("<fn>"))
+                .finish(),
+            Regex::NotSatisfy(_, label) => f
+                .debug_tuple("NotSatisfy")
+                .field(&label.as_deref().unwrap_or("<fn>"))
+                .finish(),
+            Regex::SatisfyPair(_, label) => f
+                .debug_tuple("SatisfyPair")
+                .field(&label.as_deref().unwrap_or("<pair fn>"))
+                .finish(),
             Regex::Concat(l, r) => f.debug_tuple("Concat").field(l).field(r).finish(),
             Regex::Group(r) => f.debug_tuple("Group").field(r).finish(),
             Regex::NamedGroup(name, r) => f.debug_tuple("NamedGroup").field(name).field(r).finish(),
@@ -62,60 +97,119 @@ impl<T> std::fmt::Debug for Regex<T> {
                 .field(m)
                 .field(greedy)
                 .finish(),
+            Regex::LookAhead(r) => f.debug_tuple("LookAhead").field(r).finish(),
+            Regex::NegativeLookAhead(r) => f.debug_tuple("NegativeLookAhead").field(r).finish(),
+            Regex::LookBehind(r) => f.debug_tuple("LookBehind").field(r).finish(),
+            Regex::NegativeLookBehind(r) => f.debug_tuple("NegativeLookBehind").field(r).finish(),
+            Regex::BackRef(index, _) => f.debug_tuple("BackRef").field(index).finish(),
+            Regex::NamedBackRef(name, _) => f.debug_tuple("NamedBackRef").field(name).finish(),
         }
     }
 }
 
 impl<T> std::fmt::Display for Regex<T> {
+    /// Print the regex in the syntax of `vec_reg!` macro, with minimal parentheses.
+    ///
+    /// Parentheses added to keep the structure are non-capturing groups `(?:R)`.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_with_precedence(f, Precedence::Alternation)
+    }
+}
+
+// Binding strength of regex syntax, from the loosest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Alternation,
+    Concat,
+    Repetition,
+    Atom,
+}
+
+impl<T> Regex<T> {
+    fn precedence(&self) -> Precedence {
+        match self {
+            Regex::Or(_, _) => Precedence::Alternation,
+            Regex::Concat(_, _) => Precedence::Concat,
+            Regex::Begin
+            | Regex::End
+            | Regex::ZeroOrOne(_, _)
+            | Regex::Repeat0(_, _)
+            | Regex::Repeat1(_, _)
+            | Regex::RepeatN(_, _)
+            | Regex::RepeatMinMax(_, _, _, _) => Precedence::Repetition,
+            _ => Precedence::Atom,
+        }
+    }
+
+    // Print the regex in a position which requires at least `precedence`.
+    fn fmt_with_precedence(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        precedence: Precedence,
+    ) -> std::fmt::Result {
+        if self.precedence() < precedence {
+            write!(f, "(?:")?;
+            self.fmt_with_precedence(f, Precedence::Alternation)?;
+            return write!(f, ")");
+        }
+
         match self {
             Regex::Begin => write!(f, "^"),
             Regex::End => write!(f, "$"),
-            Regex::Satisfy(_) => write!(f, "[<fn>]"),
-            Regex::NotSatisfy(_) => write!(f, "[^ <fn>]"),
-            Regex::Concat(l, r) => write!(f, "{}{}", l, r),
-            Regex::Group(r) => write!(f, "({})", r),
-            Regex::NamedGroup(r, name) => write!(f, "(P<{}>{})", name, r),
-            Regex::NonCapturingGroup(r) => write!(f, "(?:{})", r),
-            Regex::Or(l, r) => write!(f, "{}|{}", l, r),
-            Regex::Repeat0(r, greedy) => {
-                write!(f, "{}*", r)?;
-                if !greedy {
-                    write!(f, "?")?;
-                }
-
-                Ok(())
+            Regex::Satisfy(_, Some(label)) if label == "." => write!(f, "."),
+            Regex::Satisfy(_, label) => write!(f, "[{}]", label.as_deref().unwrap_or("<fn>")),
+            Regex::NotSatisfy(_, label) => {
+                write!(f, "[^{}]", label.as_deref().unwrap_or("<fn>"))
             }
-            Regex::ZeroOrOne(r, greedy) => {
-                write!(f, "{}?", r)?;
-                if !greedy {
-                    write!(f, "?")?;
-                }
-
-                Ok(())
+            Regex::SatisfyPair(_, label) => {
+                write!(f, "[{}]", label.as_deref().unwrap_or("<pair fn>"))
             }
-            Regex::Repeat1(r, greedy) => {
-                write!(f, "{}+", r)?;
-                if !greedy {
-                    write!(f, "?")?;
-                }
-                Ok(())
+            Regex::Concat(l, r) => {
+                l.fmt_with_precedence(f, Precedence::Concat)?;
+                r.fmt_with_precedence(f, Precedence::Concat)
             }
-            Regex::RepeatN(r, n) => write!(f, "{}{{{}}}", r, n),
+            Regex::Group(r) => write!(f, "({})", r),
+            Regex::NamedGroup(name, r) => write!(f, "(?P<\"{}\">{})", name, r),
+            Regex::NonCapturingGroup(r) => write!(f, "(?:{})", r),
+            Regex::Or(l, r) => {
+                l.fmt_with_precedence(f, Precedence::Alternation)?;
+                write!(f, "|")?;
+                r.fmt_with_precedence(f, Precedence::Alternation)
+            }
+            Regex::Repeat0(r, greedy) => Self::fmt_repetition(f, r, "*", *greedy),
+            Regex::ZeroOrOne(r, greedy) => Self::fmt_repetition(f, r, "?", *greedy),
+            Regex::Repeat1(r, greedy) => Self::fmt_repetition(f, r, "+", *greedy),
+            Regex::RepeatN(r, n) => Self::fmt_repetition(f, r, &format!("{{{}}}", n), true),
             Regex::RepeatMinMax(r, n, m, greedy) => {
-                if let Some(m) = m {
-                    write!(f, "{}{{{},{}}}", r, n, m)?;
-                } else {
-                    write!(f, "{}{{{},}}", r, n)?;
-                }
-                if !greedy {
-                    write!(f, "?")?;
-                }
-
-                Ok(())
+                let meta = match m {
+                    Some(m) => format!("{{{},{}}}", n, m),
+                    None => format!("{{{},}}", n),
+                };
+                Self::fmt_repetition(f, r, &meta, *greedy)
             }
+            Regex::LookAhead(r) => write!(f, "(?={})", r),
+            Regex::NegativeLookAhead(r) => write!(f, "(?!{})", r),
+            Regex::LookBehind(r) => write!(f, "(?<={})", r),
+            Regex::NegativeLookBehind(r) => write!(f, "(?<!{})", r),
+            Regex::BackRef(index, _) => write!(f, "(?P={})", index),
+            Regex::NamedBackRef(name, _) => write!(f, "(?P=\"{}\")", name),
         }
     }
+
+    fn fmt_repetition(
+        f: &mut std::fmt::Formatter<'_>,
+        r: &Regex<T>,
+        meta: &str,
+        greedy: bool,
+    ) -> std::fmt::Result {
+        r.fmt_with_precedence(f, Precedence::Atom)?;
+        write!(f, "{}", meta)?;
+        if !greedy {
+            write!(f, "?")?;
+        }
+
+        Ok(())
+    }
 }
 
 impl<T: 'static> Regex<T> {
@@ -128,18 +222,71 @@ impl<T: 'static> Regex<T> {
         Regex::End
     }
     /// Like a `[character class]` in regex. Build regex that matches any value that satisfies the given predicate.
-    pub fn satisfy(f: impl Fn(&T) -> bool + 'static) -> Self {
-        Regex::Satisfy(Rc::new(f))
+    ///
+    /// Predicates must be `Send + Sync` so that regex and compiled regex can be shared between threads.
+    pub fn satisfy(f: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        Regex::Satisfy(Arc::new(f), None)
+    }
+
+    /// Same as `satisfy`, but the predicate is labeled by `name`,
+    /// which is shown by `Display`, `Debug` and the program listing instead of `<fn>`.
+    ///
+    /// ```
+    /// use vec_reg_common::Regex;
+    ///
+    /// let reg = Regex::repeat1(Regex::satisfy_named("is_fizz", |x: &i32| x % 3 == 0), true);
+    /// assert_eq!(reg.to_string(), "[is_fizz]+");
+    /// ```
+    pub fn satisfy_named(name: &str, f: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        Regex::Satisfy(Arc::new(f), Some(name.to_owned()))
     }
 
     /// Like a `[^character class]` in regex. Build regex that matches any value that not satisfies the given predicate.
-    pub fn not_satisfy(f: impl Fn(&T) -> bool + 'static) -> Self {
-        Regex::NotSatisfy(Rc::new(move |x| !f(x)))
+    pub fn not_satisfy(f: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        Regex::NotSatisfy(Arc::new(move |x| !f(x)), None)
+    }
+
+    /// Same as `not_satisfy`, but the predicate is labeled by `name`.
+    pub fn not_satisfy_named(name: &str, f: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        Regex::NotSatisfy(Arc::new(move |x| !f(x)), Some(name.to_owned()))
     }
 
-    /// Like a `.` in regex. Build regex that matches any value.
+    /// Build regex that matches any value that satisfies the given predicate, called with the previous value and the value.
+    /// It never matches the first value of the input, since there is no previous value.
+    pub fn satisfy_pair(f: impl Fn(&T, &T) -> bool + Send + Sync + 'static) -> Self {
+        Regex::SatisfyPair(Arc::new(f), None)
+    }
+
+    /// Same as `satisfy_pair`, but the predicate is labeled by `name`.
+    pub fn satisfy_pair_named(
+        name: &str,
+        f: impl Fn(&T, &T) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Regex::SatisfyPair(Arc::new(f), Some(name.to_owned()))
+    }
+
+    /// Build regex that matches any value that not satisfies the given predicate, called with the previous value and the value.
+    /// It never matches the first value of the input, since there is no previous value.
+    pub fn not_satisfy_pair(f: impl Fn(&T, &T) -> bool + Send + Sync + 'static) -> Self {
+        Regex::SatisfyPair(Arc::new(move |prev, cur| !f(prev, cur)), None)
+    }
+
+    /// Same as `not_satisfy_pair`, but the predicate is labeled by `name`.
+    ///
+    /// The label is stored as `^name`, since the negation is part of the pair predicate.
+    pub fn not_satisfy_pair_named(
+        name: &str,
+        f: impl Fn(&T, &T) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Regex::SatisfyPair(
+            Arc::new(move |prev, cur| !f(prev, cur)),
+            Some(format!("^{}", name)),
+        )
+    }
+
+    /// Like a `.` in regex. Build regex that matches any value, labeled by `.`.
     pub fn any() -> Self {
-        Regex::Satisfy(Rc::new(|_| true))
+        Regex::Satisfy(Arc::new(|_| true), Some(".".to_owned()))
     }
 
     /// Like a `?`, `??` in regex. Build regex that matches underlying regex zero or one times.
@@ -197,18 +344,62 @@ impl<T: 'static> Regex<T> {
         Regex::NamedGroup(name.to_owned(), r.into())
     }
 
+    /// Like a `(?=R)` in regex. Build regex that asserts R matches at the current position, without consuming it.
+    ///
+    /// Capture groups inside the assertion don't capture.
+    pub fn look_ahead(r: Self) -> Self {
+        Regex::LookAhead(r.into())
+    }
+
+    /// Like a `(?!R)` in regex. Build regex that asserts R does not match at the current position.
+    pub fn negative_look_ahead(r: Self) -> Self {
+        Regex::NegativeLookAhead(r.into())
+    }
+
+    /// Like a `(?<=R)` in regex. Build regex that asserts R matches a sub-slice ending at the current position.
+    ///
+    /// Capture groups inside the assertion don't capture.
+    pub fn look_behind(r: Self) -> Self {
+        Regex::LookBehind(r.into())
+    }
+
+    /// Like a `(?<!R)` in regex. Build regex that asserts R does not match any sub-slice ending at the current position.
+    pub fn negative_look_behind(r: Self) -> Self {
+        Regex::NegativeLookBehind(r.into())
+    }
+
+    /// Like a `\1` in regex. Build regex that matches the same values captured by the numbered group.
+    ///
+    /// It doesn't match if the group didn't participate in the match so far.
+    pub fn back_ref(group_index: usize) -> Self
+    where
+        T: PartialEq,
+    {
+        Regex::BackRef(group_index, Arc::new(|a: &T, b: &T| a == b))
+    }
+
+    /// Like a `\k<name>` in regex. Build regex that matches the same values captured by the named group.
+    ///
+    /// It doesn't match if the group didn't participate in the match so far.
+    pub fn named_back_ref(name: &str) -> Self
+    where
+        T: PartialEq,
+    {
+        Regex::NamedBackRef(name.to_owned(), Arc::new(|a: &T, b: &T| a == b))
+    }
+
     /// Build regex that matches given value.
     pub fn is(value: T) -> Self
     where
-        T: PartialEq + 'static,
+        T: PartialEq + Send + Sync + 'static,
     {
-        Regex::Satisfy(Rc::new(move |v| *v == value))
+        Regex::Satisfy(Arc::new(move |v| *v == value), None)
     }
 
     /// Build regex that matches given value sequence.
     pub fn seq(values: &[T]) -> Self
     where
-        T: PartialEq + Clone + 'static,
+        T: PartialEq + Clone + Send + Sync + 'static,
     {
         if values.len() == 1 {
             Regex::is(values[0].clone())
@@ -221,7 +412,47 @@ impl<T: 'static> Regex<T> {
         }
     }
 
-    pub fn compile(self) -> impl CompiledRegex<T> {
+    /// Parse a pattern written in the same syntax as `vec_reg!` macro.
+    ///
+    /// `[name]` and `[^name]` refer to predicates registered in `registry`, instead of functions or closures.
+    /// Back references compare values by the function set by `PredicateRegistry::set_eq`.
+    /// Whitespaces between tokens are ignored.
+    ///
+    /// ```
+    /// use vec_reg_common::{CompiledRegex, PredicateRegistry, Regex};
+    ///
+    /// let mut registry = PredicateRegistry::new();
+    /// registry.insert("is_fizz", |x: &i32| x % 3 == 0);
+    /// registry.insert("is_buzz", |x: &i32| x % 5 == 0);
+    /// let reg = Regex::parse("[is_fizz]([is_buzz]|[^is_fizz])+", &registry).unwrap().compile();
+    /// assert!(reg.is_full_match(&[3, 5, 10, 7]));
+    ///
+    /// let error = Regex::parse("[is_fizz][is_even]", &registry).unwrap_err();
+    /// assert_eq!(error.position(), 10);
+    /// ```
+    pub fn parse(pattern: &str, registry: &PredicateRegistry<T>) -> Result<Self, ParseError> {
+        parser::parse(pattern, registry)
+    }
+
+    /// Compile regex into a program executable by the virtual machine.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regex is invalid or too large. Use `try_compile` to handle these errors.
+    pub fn compile(self) -> CompiledRegexInVm<T> {
         CompiledRegexInVm::compile(self)
     }
+
+    /// Compile regex into a program executable by the virtual machine with default options.
+    pub fn try_compile(self) -> Result<CompiledRegexInVm<T>, CompileError> {
+        CompiledRegexInVm::try_compile(self, &CompileOptions::default())
+    }
+
+    /// Compile regex into a program executable by the virtual machine with given options.
+    pub fn try_compile_with(
+        self,
+        options: &CompileOptions,
+    ) -> Result<CompiledRegexInVm<T>, CompileError> {
+        CompiledRegexInVm::try_compile(self, options)
+    }
 }
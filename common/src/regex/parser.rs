@@ -0,0 +1,332 @@
+use std::{collections::HashMap, sync::Arc};
+
+use super::{EqFn, PairFn, Regex};
+use crate::{ParseError, ParseErrorKind};
+
+/// Named predicates referred by `[name]` in a pattern parsed by `Regex::parse`.
+pub struct PredicateRegistry<T> {
+    predicates: HashMap<String, Predicate<T>>,
+    eq: Option<EqFn<T>>,
+}
+
+enum Predicate<T> {
+    Value(Arc<dyn Fn(&T) -> bool + Send + Sync>),
+    Pair(PairFn<T>),
+}
+
+impl<T> PredicateRegistry<T> {
+    pub fn new() -> Self {
+        Self {
+            predicates: HashMap::new(),
+            eq: None,
+        }
+    }
+
+    /// Register a predicate on a value as `name`, replacing any predicate registered with the same name.
+    pub fn insert(&mut self, name: &str, f: impl Fn(&T) -> bool + Send + Sync + 'static) {
+        self.predicates
+            .insert(name.to_owned(), Predicate::Value(Arc::new(f)));
+    }
+
+    /// Register a predicate on the previous value and the value as `name`,
+    /// replacing any predicate registered with the same name.
+    pub fn insert_pair(&mut self, name: &str, f: impl Fn(&T, &T) -> bool + Send + Sync + 'static) {
+        self.predicates
+            .insert(name.to_owned(), Predicate::Pair(Arc::new(f)));
+    }
+
+    /// Set the function comparing captured values with values at back references `(?P=...)`.
+    ///
+    /// Patterns with back references fail to parse without it.
+    pub fn set_eq(&mut self, f: impl Fn(&T, &T) -> bool + Send + Sync + 'static) {
+        self.eq = Some(Arc::new(f));
+    }
+
+    /// Returns true if a predicate is registered as `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.predicates.contains_key(name)
+    }
+}
+
+impl<T> Default for PredicateRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse `pattern` written in the syntax of `vec_reg!` macro, except that `[...]` only accepts predicate names.
+pub(super) fn parse<T: 'static>(
+    pattern: &str,
+    registry: &PredicateRegistry<T>,
+) -> Result<Regex<T>, ParseError> {
+    let mut parser = Parser {
+        pattern,
+        chars: pattern.char_indices().collect(),
+        pos: 0,
+        registry,
+    };
+    let reg = parser.parse_expr()?;
+    parser.skip_whitespace();
+    match parser.peek() {
+        None => Ok(reg),
+        Some(c) => Err(parser.error(ParseErrorKind::UnexpectedChar(c))),
+    }
+}
+
+struct Parser<'a, T> {
+    pattern: &'a str,
+    chars: Vec<(usize, char)>,
+    pos: usize,
+    registry: &'a PredicateRegistry<T>,
+}
+
+impl<'a, T: 'static> Parser<'a, T> {
+    fn parse_expr(&mut self) -> Result<Regex<T>, ParseError> {
+        let mut reg = self.parse_term()?;
+        while self.eat('|') {
+            reg = Regex::or(reg, self.parse_term()?);
+        }
+
+        Ok(reg)
+    }
+
+    fn parse_term(&mut self) -> Result<Regex<T>, ParseError> {
+        let mut reg = self.parse_factor()?;
+        while !matches!(self.peek(), None | Some('|') | Some(')')) {
+            reg = Regex::concat(reg, self.parse_factor()?);
+        }
+
+        Ok(reg)
+    }
+
+    fn parse_factor(&mut self) -> Result<Regex<T>, ParseError> {
+        if self.eat('^') {
+            Ok(Regex::begin())
+        } else if self.eat('$') {
+            Ok(Regex::end())
+        } else {
+            let atom = self.parse_atom()?;
+            self.parse_optional_meta_character(atom)
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Regex<T>, ParseError> {
+        if self.eat('.') {
+            Ok(Regex::any())
+        } else if self.eat('[') {
+            self.parse_satisfy()
+        } else if self.eat('(') {
+            let reg = self.parse_group()?;
+            self.expect(')')?;
+            Ok(reg)
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    // parse name] or ^name] after [
+    fn parse_satisfy(&mut self) -> Result<Regex<T>, ParseError> {
+        let inverse = self.eat('^');
+        self.skip_whitespace();
+        let start = self.offset();
+        let name = self.parse_name();
+        if name.is_empty() {
+            return Err(self.unexpected());
+        }
+        let predicate = match self.registry.predicates.get(name) {
+            Some(predicate) => predicate,
+            None => {
+                return Err(ParseError::new(
+                    start,
+                    ParseErrorKind::UnknownPredicate(name.to_owned()),
+                ))
+            }
+        };
+        self.expect(']')?;
+
+        let label = Some(name.to_owned());
+        Ok(match (predicate, inverse) {
+            (Predicate::Value(f), false) => Regex::Satisfy(f.clone(), label),
+            (Predicate::Value(f), true) => {
+                let f = f.clone();
+                Regex::NotSatisfy(Arc::new(move |x| !f(x)), label)
+            }
+            (Predicate::Pair(f), false) => Regex::SatisfyPair(f.clone(), label),
+            (Predicate::Pair(f), true) => {
+                let f = f.clone();
+                Regex::SatisfyPair(
+                    Arc::new(move |prev, cur| !f(prev, cur)),
+                    Some(format!("^{}", name)),
+                )
+            }
+        })
+    }
+
+    // parse the content of a group after (
+    fn parse_group(&mut self) -> Result<Regex<T>, ParseError> {
+        if !self.eat('?') {
+            return Ok(Regex::group(self.parse_expr()?));
+        }
+
+        if self.eat(':') {
+            Ok(Regex::non_capturing_group(self.parse_expr()?))
+        } else if self.eat('=') {
+            Ok(Regex::look_ahead(self.parse_expr()?))
+        } else if self.eat('!') {
+            Ok(Regex::negative_look_ahead(self.parse_expr()?))
+        } else if self.eat('<') {
+            if self.eat('=') {
+                Ok(Regex::look_behind(self.parse_expr()?))
+            } else if self.eat('!') {
+                Ok(Regex::negative_look_behind(self.parse_expr()?))
+            } else {
+                Err(self.unexpected())
+            }
+        } else if self.eat('P') {
+            if self.eat('=') {
+                let eq = match &self.registry.eq {
+                    Some(eq) => eq.clone(),
+                    None => return Err(self.error(ParseErrorKind::BackRefWithoutEq)),
+                };
+                self.skip_whitespace();
+                if self.peek() == Some('"') {
+                    Ok(Regex::NamedBackRef(self.parse_string()?, eq))
+                } else {
+                    Ok(Regex::BackRef(self.parse_number()?, eq))
+                }
+            } else {
+                self.expect('<')?;
+                self.skip_whitespace();
+                let name = self.parse_string()?;
+                self.expect('>')?;
+                Ok(Regex::named_group(&name, self.parse_expr()?))
+            }
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    // parse ?, +, *, {n}, {n,}, {n,m} meta characters.
+    fn parse_optional_meta_character(&mut self, reg: Regex<T>) -> Result<Regex<T>, ParseError> {
+        if self.eat('?') {
+            let greedy = !self.eat('?');
+            Ok(Regex::zero_or_one(reg, greedy))
+        } else if self.eat('*') {
+            let greedy = !self.eat('?');
+            Ok(Regex::repeat0(reg, greedy))
+        } else if self.eat('+') {
+            let greedy = !self.eat('?');
+            Ok(Regex::repeat1(reg, greedy))
+        } else if self.eat('{') {
+            let n = self.parse_number()?;
+            let reg = if self.eat(',') {
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.expect('}')?;
+                    let greedy = !self.eat('?');
+                    Regex::repeat_n_or_more(reg, n, greedy)
+                } else {
+                    let m = self.parse_number()?;
+                    self.expect('}')?;
+                    let greedy = !self.eat('?');
+                    Regex::repeat_min_max(reg, n, m, greedy)
+                }
+            } else {
+                self.expect('}')?;
+                self.eat('?');
+                Regex::repeat_n(reg, n)
+            };
+            Ok(reg)
+        } else {
+            Ok(reg)
+        }
+    }
+
+    fn parse_name(&mut self) -> &'a str {
+        let start = self.offset();
+        while matches!(self.chars.get(self.pos), Some((_, c)) if c.is_alphanumeric() || *c == '_') {
+            self.pos += 1;
+        }
+
+        &self.pattern[start..self.offset()]
+    }
+
+    fn parse_number(&mut self) -> Result<usize, ParseError> {
+        self.skip_whitespace();
+        let start = self.offset();
+        while matches!(self.chars.get(self.pos), Some((_, c)) if c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits = &self.pattern[start..self.offset()];
+        if digits.is_empty() {
+            return Err(self.unexpected());
+        }
+
+        digits
+            .parse()
+            .map_err(|_| ParseError::new(start, ParseErrorKind::InvalidNumber(digits.to_owned())))
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+        let start = self.offset();
+        loop {
+            match self.chars.get(self.pos) {
+                Some((_, '"')) => break,
+                Some(_) => self.pos += 1,
+                None => return Err(self.error(ParseErrorKind::UnexpectedEnd)),
+            }
+        }
+        let string = self.pattern[start..self.offset()].to_owned();
+        self.pos += 1;
+
+        Ok(string)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.get(self.pos), Some((_, c)) if c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    // Next character after whitespaces.
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).map(|(_, c)| *c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    // Byte offset of the current character.
+    fn offset(&self) -> usize {
+        self.chars
+            .get(self.pos)
+            .map_or(self.pattern.len(), |(offset, _)| *offset)
+    }
+
+    fn unexpected(&mut self) -> ParseError {
+        match self.peek() {
+            Some(c) => self.error(ParseErrorKind::UnexpectedChar(c)),
+            None => self.error(ParseErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError::new(self.offset(), kind)
+    }
+}
@@ -1,5 +1,17 @@
+mod backtrack;
 mod compiler;
 mod inst;
+mod options;
+mod render;
 mod runner;
+mod set;
+mod stream;
+mod trace;
 
 pub use compiler::CompiledRegexInVm;
+pub use options::{
+    Anchored, CompileOptions, Engine, MatchKind, DEFAULT_BACKTRACK_LIMIT, DEFAULT_SIZE_LIMIT,
+};
+pub use set::{RegexSet, SetMatches};
+pub use stream::{IterMatches, MatchEvent, StreamMatcher};
+pub use trace::{MatchTrace, PredicateTest, TraceStep, TraceThread};
@@ -0,0 +1,350 @@
+use crate::MatchError;
+
+use super::inst::{Inst, PC, SP};
+use super::options::{Anchored, MatchKind};
+use super::runner::{run_look, Search, Slots};
+
+enum Job {
+    // Explore `pc` at `sp`. `splits` is the list of `Inst::Split` visited since the last consumed value,
+    // used to cut infinite loops on repetition of empty match when visited states are not tracked.
+    Explore { pc: PC, sp: SP, splits: Vec<PC> },
+    // Restore saved position on backtrack.
+    RestoreSlot(usize, Option<SP>),
+}
+
+// Maximum number of (pc, sp) states the visited set may track, the same as RE2's BitState.
+const MAX_VISITED_STATES: usize = 256 * 1024;
+
+/// Returns whether the visited set for a search from `start` fits in the cap.
+/// Above the cap, a program without back references should run on the Pike VM instead.
+pub fn visited_fits(prog_size: usize, input_len: usize, start: SP) -> bool {
+    prog_size.saturating_mul(input_len.saturating_sub(start) + 1) <= MAX_VISITED_STATES
+}
+
+// Set of visited (pc, sp) pairs, like RE2's BitState.
+//
+// The first visit of a state has the highest priority, so visiting it again can never find a better match.
+// The set grows with the furthest position the search reaches rather than the remaining input,
+// so that a search stopping at a short match doesn't pay for the rest of the input.
+struct Visited {
+    bits: Vec<u64>,
+    prog_size: usize,
+    start: SP,
+}
+
+impl Visited {
+    fn new(prog_size: usize, start: SP) -> Self {
+        Self {
+            bits: vec![],
+            prog_size,
+            start,
+        }
+    }
+
+    // Mark the state as visited, and returns whether it was already visited.
+    fn insert(&mut self, pc: PC, sp: SP) -> bool {
+        let key = (sp - self.start) * self.prog_size + pc;
+        let (word, bit) = (key / 64, 1 << (key % 64));
+        if word >= self.bits.len() {
+            self.bits.resize((word + 1).max(self.bits.len() * 2), 0);
+        }
+        let visited = self.bits[word] & bit != 0;
+        self.bits[word] |= bit;
+        visited
+    }
+}
+
+/// How the backtracker bounds its running time.
+#[derive(Debug, Clone, Copy)]
+pub enum Bound {
+    /// Explore each (pc, sp) pair at most once.
+    Visited,
+    /// Follow at most the given number of paths in a search.
+    /// `Inst::BackRef` depends on captured positions, so visited states can't be skipped.
+    Steps(usize),
+}
+
+// Paths left to follow in a search, shared by every start position.
+struct Budget {
+    remaining: usize,
+    limit: usize,
+}
+
+impl Budget {
+    fn new(bound: Bound) -> Self {
+        let limit = match bound {
+            Bound::Visited => usize::MAX,
+            Bound::Steps(limit) => limit,
+        };
+        Self {
+            remaining: limit,
+            limit,
+        }
+    }
+
+    fn spend(&mut self) -> Result<(), MatchError> {
+        if self.remaining == 0 {
+            return Err(MatchError::BacktrackLimitExceeded { limit: self.limit });
+        }
+        self.remaining -= 1;
+        Ok(())
+    }
+}
+
+/// Run the program over `input` by backtracking, starting the search at `start`, and returns saved positions of the match.
+///
+/// Alternatives are explored in priority order, so the first path reaching `Inst::Match`
+/// is the same leftmost-first match as the Pike VM reports.
+/// Only the first `search.slot_count` slots are tracked, but back references need every slot.
+/// With `MatchKind::LeftmostLongest`, every path from the leftmost start position is explored
+/// to find the longest match.
+/// With `Bound::Steps`, `Inst::BackRef` is supported, and an error is returned once the search follows too many paths.
+pub fn run_backtrack<I>(
+    insts: &[Inst<I>],
+    input: &[I],
+    start: SP,
+    search: Search,
+    bound: Bound,
+) -> Result<Option<Slots>, MatchError> {
+    if start > input.len() {
+        return Ok(None);
+    }
+
+    let mut visited = match bound {
+        Bound::Visited => Some(Visited::new(insts.len(), start)),
+        Bound::Steps(_) => None,
+    };
+    let mut budget = Budget::new(bound);
+    let mut slots = vec![None; search.slot_count];
+    let mut stack = vec![];
+    let last_start = if search.anchored == Anchored::No {
+        input.len()
+    } else {
+        start
+    };
+    // Try each start position in order, so that the first match found is the leftmost one.
+    // A state visited from an earlier start position fails again, so visited set is shared.
+    for search_start in start..=last_start {
+        let mut longest: Option<Slots> = None;
+        let mut first = None;
+        explore(
+            insts,
+            input,
+            search_start,
+            search.anchored,
+            &mut visited,
+            &mut budget,
+            &mut slots,
+            &mut stack,
+            |slots| {
+                if search.earliest || search.match_kind == MatchKind::LeftmostFirst {
+                    first = Some(slots.to_vec());
+                    return true;
+                }
+                if longest.as_ref().is_none_or(|l| slots[1] > l[1]) {
+                    longest = Some(slots.to_vec());
+                }
+                false
+            },
+        )?;
+        if first.is_some() {
+            return Ok(first);
+        }
+        if longest.is_some() {
+            return Ok(longest);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Run the program by backtracking anchored at `start`, and returns end positions of every match in ascending order.
+///
+/// `search.slot_count` must be at least 2, so that the end of the 0th group is tracked.
+pub fn run_backtrack_ends<I>(
+    insts: &[Inst<I>],
+    input: &[I],
+    start: SP,
+    search: Search,
+    bound: Bound,
+) -> Result<Vec<SP>, MatchError> {
+    if start > input.len() {
+        return Ok(vec![]);
+    }
+
+    let mut visited = match bound {
+        Bound::Visited => Some(Visited::new(insts.len(), start)),
+        Bound::Steps(_) => None,
+    };
+    let mut budget = Budget::new(bound);
+    let mut slots = vec![None; search.slot_count];
+    let mut ends = vec![];
+    explore(
+        insts,
+        input,
+        start,
+        search.anchored,
+        &mut visited,
+        &mut budget,
+        &mut slots,
+        &mut vec![],
+        |slots| {
+            if let Some(end) = slots[1] {
+                ends.push(end);
+            }
+            false
+        },
+    )?;
+    ends.sort_unstable();
+    ends.dedup();
+
+    Ok(ends)
+}
+
+// Explore paths from `start` in priority order, calling `on_match` with saved positions of each match.
+// Stops exploring if `on_match` returns true, and returns whether it stopped.
+// Each path followed is spent from `budget`, and an error is returned when it runs out.
+#[allow(clippy::too_many_arguments)]
+fn explore<I>(
+    insts: &[Inst<I>],
+    input: &[I],
+    start: SP,
+    anchored: Anchored,
+    visited: &mut Option<Visited>,
+    budget: &mut Budget,
+    slots: &mut [Option<SP>],
+    stack: &mut Vec<Job>,
+    mut on_match: impl FnMut(&[Option<SP>]) -> bool,
+) -> Result<bool, MatchError> {
+    stack.push(Job::Explore {
+        pc: 0,
+        sp: start,
+        splits: vec![],
+    });
+    while let Some(job) = stack.pop() {
+        match job {
+            Job::RestoreSlot(slot, value) => slots[slot] = value,
+            Job::Explore { pc, sp, splits } => {
+                if let Err(err) = budget.spend() {
+                    stack.clear();
+                    return Err(err);
+                }
+                if step(
+                    insts, input, pc, sp, splits, anchored, visited, slots, stack,
+                ) && on_match(slots)
+                {
+                    stack.clear();
+                    return Ok(true);
+                }
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+// Follow a single path until it matches or fails, pushing lower priority alternatives to `stack`.
+#[allow(clippy::too_many_arguments)]
+fn step<I>(
+    insts: &[Inst<I>],
+    input: &[I],
+    mut pc: PC,
+    mut sp: SP,
+    mut splits: Vec<PC>,
+    anchored: Anchored,
+    visited: &mut Option<Visited>,
+    slots: &mut [Option<SP>],
+    stack: &mut Vec<Job>,
+) -> bool {
+    loop {
+        if let Some(visited) = visited {
+            if visited.insert(pc, sp) {
+                return false;
+            }
+        }
+        match &insts[pc] {
+            Inst::Match => return anchored != Anchored::Both || sp == input.len(),
+            Inst::Begin => {
+                if sp != 0 {
+                    return false;
+                }
+                pc += 1;
+            }
+            Inst::End => {
+                if sp != input.len() {
+                    return false;
+                }
+                pc += 1;
+            }
+            Inst::Check(predicate, _) => {
+                if sp == input.len() || !predicate.test(input, sp) {
+                    return false;
+                }
+                pc += 1;
+                sp += 1;
+                splits.clear();
+            }
+            Inst::Jmp(x) => pc = *x,
+            Inst::Split(x, y) => {
+                // Visited set already cuts repetition of empty match.
+                if visited.is_none() {
+                    if splits.contains(&pc) {
+                        return false;
+                    }
+                    splits.push(pc);
+                }
+                stack.push(Job::Explore {
+                    pc: *y,
+                    sp,
+                    splits: splits.clone(),
+                });
+                pc = *x;
+            }
+            Inst::SaveOpen(group_index) | Inst::SaveNamedOpen(_, group_index) => {
+                save(slots, stack, group_index * 2, sp);
+                pc += 1;
+            }
+            Inst::SaveClose(group_index) | Inst::SaveNamedClose(_, group_index) => {
+                save(slots, stack, group_index * 2 + 1, sp);
+                pc += 1;
+            }
+            Inst::LookAhead(look_insts, negate) => {
+                if run_look(look_insts, input, sp, false) == *negate {
+                    return false;
+                }
+                pc += 1;
+            }
+            Inst::LookBehind(look_insts, negate) => {
+                if run_look(look_insts, input, sp, true) == *negate {
+                    return false;
+                }
+                pc += 1;
+            }
+            Inst::BackRef(group_index, eq) => {
+                let captured = match (slots[group_index * 2], slots[group_index * 2 + 1]) {
+                    (Some(start), Some(end)) if start <= end => &input[start..end],
+                    _ => return false,
+                };
+                let rest = &input[sp..];
+                if rest.len() < captured.len()
+                    || !captured.iter().zip(rest.iter()).all(|(a, b)| eq(a, b))
+                {
+                    return false;
+                }
+                pc += 1;
+                if !captured.is_empty() {
+                    sp += captured.len();
+                    splits.clear();
+                }
+            }
+        }
+    }
+}
+
+// Save the position to the slot, unless the slot is not tracked.
+fn save(slots: &mut [Option<SP>], stack: &mut Vec<Job>, slot: usize, sp: SP) {
+    if let Some(value) = slots.get_mut(slot) {
+        stack.push(Job::RestoreSlot(slot, *value));
+        *value = Some(sp);
+    }
+}
@@ -1,110 +1,560 @@
 use core::panic;
-use std::rc::Rc;
+use std::{collections::HashMap, sync::Arc};
 
-use crate::{CaptureLocation, Captures, CompiledRegex, Match, Regex};
+use crate::{CaptureLocation, Captures, CompileError, CompiledRegex, Match, MatchError, Regex};
 
-use super::inst::{GroupIndex, Inst, PC};
+use super::{
+    backtrack::{visited_fits, Bound},
+    inst::{GroupIndex, Inst, Predicate, PC},
+    options::{Anchored, CompileOptions, Engine, MatchKind},
+    runner::{run_vm_traced, Search, Slots},
+    trace::MatchTrace,
+};
 
+/// Compiled regex program executed by the virtual machine.
+///
+/// This is the type returned by `Regex::compile`, so it can be stored in struct fields or collections.
 pub struct CompiledRegexInVm<I> {
-    insts: Vec<Inst<I>>,
+    pub(super) insts: Vec<Inst<I>>,
+    group_names: Vec<Option<String>>,
+    named_capture_index: Arc<HashMap<String, GroupIndex>>,
+    engine: Engine,
+    pub(super) anchored: Anchored,
+    match_kind: MatchKind,
+    // Back reference depends on captured values, so the backtracker can't skip visited states.
+    has_back_ref: bool,
+    backtrack_limit: usize,
 }
 
 impl<I> CompiledRegexInVm<I> {
+    /// Compile regex with default options.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the regex is invalid or too large.
     pub fn compile(reg: Regex<I>) -> Self {
-        // Wrapping given regex R in `.*?(R).*?` to partial matching.
-        let full_match_regex = Regex::Concat(
-            Rc::new(Regex::Repeat0(
-                Rc::new(Regex::Satisfy(Rc::new(|_| true))),
-                false,
-            )),
-            Rc::new(Regex::Concat(
-                Rc::new(Regex::Group(reg.into())),
-                Rc::new(Regex::Repeat0(
-                    Rc::new(Regex::Satisfy(Rc::new(|_| true))),
-                    false,
-                )),
-            )),
-        );
-        let insts = compile_regex_to_vm_insts(&full_match_regex);
+        match Self::try_compile(reg, &CompileOptions::default()) {
+            Ok(compiled) => compiled,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Compile regex with given options, returning an error if the regex is invalid or too large.
+    pub fn try_compile(reg: Regex<I>, options: &CompileOptions) -> Result<Self, CompileError> {
+        let pattern = Pattern::new(reg)?;
+        if pattern.size > options.size_limit {
+            return Err(CompileError::SizeLimitExceeded {
+                size: pattern.size,
+                limit: options.size_limit,
+            });
+        }
+        let group_names = pattern.group_names();
+        let (insts, named_indices) = pattern.compile(0);
+        let has_back_ref = insts.iter().any(|inst| matches!(inst, Inst::BackRef(_, _)));
+        let engine = match options.engine {
+            Some(Engine::PikeVm) if has_back_ref => return Err(CompileError::BackRefNotSupported),
+            Some(engine) => engine,
+            None if has_back_ref => Engine::Backtrack,
+            None => Engine::PikeVm,
+        };
+
+        Ok(Self {
+            insts,
+            group_names,
+            named_capture_index: Arc::new(named_indices),
+            engine,
+            anchored: options.anchored,
+            match_kind: options.match_kind,
+            has_back_ref,
+            backtrack_limit: options.backtrack_limit,
+        })
+    }
+
+    // Run the program tracking the first `slot_count` slots, with the compiled anchoring.
+    // If `earliest` is true, the returned positions may not be the leftmost-first match.
+    fn run(
+        &self,
+        input: &[I],
+        start: usize,
+        slot_count: usize,
+        earliest: bool,
+    ) -> Result<Option<Slots>, MatchError> {
+        self.run_anchored(input, start, slot_count, earliest, self.anchored)
+    }
 
-        Self { insts }
+    fn run_anchored(
+        &self,
+        input: &[I],
+        start: usize,
+        slot_count: usize,
+        earliest: bool,
+        anchored: Anchored,
+    ) -> Result<Option<Slots>, MatchError> {
+        let all_slots = self.group_names.len() * 2;
+        let slot_count = if self.has_back_ref {
+            all_slots
+        } else if self.match_kind == MatchKind::LeftmostLongest && !earliest {
+            // Compare start and end of matches.
+            slot_count.max(2).min(all_slots)
+        } else {
+            slot_count.min(all_slots)
+        };
+        let search = Search {
+            slot_count,
+            earliest,
+            anchored,
+            match_kind: self.match_kind,
+        };
+        if self.backtracks(input, start) {
+            super::backtrack::run_backtrack(&self.insts, input, start, search, self.bound())
+        } else {
+            Ok(super::runner::run_vm(&self.insts, input, start, search))
+        }
     }
 
-    #[allow(dead_code)]
-    pub fn dump_insts(&self) {
-        eprintln!("Instructions:");
-        for i in 0..self.insts.len() {
-            eprintln!("{}\t{:?}", i, self.insts[i]);
+    // Without back references, the backtracker skips visited states.
+    // Otherwise, the number of paths it follows is limited.
+    fn bound(&self) -> Bound {
+        if self.has_back_ref {
+            Bound::Steps(self.backtrack_limit)
+        } else {
+            Bound::Visited
         }
     }
-}
 
-impl<I> CompiledRegex<I> for CompiledRegexInVm<I> {
-    fn is_match(&self, input: &[I]) -> bool {
-        super::runner::run_vm(&self.insts, input).is_some()
-    }
-
-    fn find<'a>(&self, input: &'a [I]) -> Option<Match<'a, I>> {
-        if let Some(matched_thread) = super::runner::run_vm(&self.insts, input) {
-            let saved = matched_thread.saved;
-            if let Some(start) = saved.get(&0) {
-                if let Some(end) = saved.get(&1) {
-                    Some(Match {
-                        input,
-                        start: *start,
-                        end: *end,
-                    })
-                } else {
-                    panic!("Unexpected asymmetric saved position.")
-                }
-            } else {
-                panic!("Unexpected missing 0th capture.")
+    // Whether the search from `start` runs on the backtracker.
+    // Without back references, a search whose visited set would exceed the cap falls back to the Pike VM.
+    fn backtracks(&self, input: &[I], start: usize) -> bool {
+        match self.engine {
+            Engine::PikeVm => false,
+            Engine::Backtrack => {
+                self.has_back_ref || visited_fits(self.insts.len(), input.len(), start)
             }
-        } else {
-            None
         }
     }
 
-    fn captures<'a>(&self, input: &'a [I]) -> Option<Captures<'a, I>> {
-        if let Some(matched_thread) = super::runner::run_vm(&self.insts, input) {
-            let saved = matched_thread.saved;
-            let mut capture_locations = vec![];
-            for i in 0.. {
-                if let Some(start) = saved.get(&(i * 2)) {
-                    if let Some(end) = saved.get(&(i * 2 + 1)) {
-                        capture_locations.push(CaptureLocation {
-                            start: *start,
-                            end: *end,
-                        });
+    /// Returns every match in the slice, ordered by start and then end position.
+    ///
+    /// Unlike `find_overlapping_iter`, which reports one match per start position,
+    /// every end position that completes a match from each start position is reported,
+    /// regardless of greedy and non-greedy preferences.
+    ///
+    /// If a search of a regex with back references exceeds the backtrack limit,
+    /// only spans starting before the position of that search are returned.
+    pub fn find_all_spans<'t>(&self, input: &'t [I]) -> Vec<Match<'t, I>> {
+        let last_start = if self.anchored == Anchored::No {
+            input.len()
+        } else {
+            0
+        };
+        let anchored = if self.anchored == Anchored::Both {
+            Anchored::Both
+        } else {
+            Anchored::Start
+        };
+        let mut spans = vec![];
+        for start in 0..=last_start {
+            let ends = if self.backtracks(input, start) {
+                let search = Search {
+                    slot_count: if self.has_back_ref {
+                        self.group_names.len() * 2
                     } else {
-                        panic!("Unexpected asymmetric saved position.")
-                    }
-                } else {
-                    break;
+                        2
+                    },
+                    earliest: false,
+                    anchored,
+                    match_kind: self.match_kind,
+                };
+                match super::backtrack::run_backtrack_ends(
+                    &self.insts,
+                    input,
+                    start,
+                    search,
+                    self.bound(),
+                ) {
+                    Ok(ends) => ends,
+                    Err(_) => break,
                 }
-            }
-            Some(Captures {
-                input,
-                capture_locations,
-                named_capture_index: matched_thread.named_capture_index,
+            } else {
+                super::runner::run_vm_ends(&self.insts, input, start, anchored == Anchored::Both)
+            };
+            spans.extend(ends.into_iter().map(|end| Match { input, start, end }));
+        }
+
+        spans
+    }
+
+    /// Run the leftmost-first search of `find` on the Pike VM, recording the threads alive at each position
+    /// and the predicates tested there, to see where the regex got stuck.
+    ///
+    /// Returns an error if the regex contains back references, which the Pike VM doesn't support.
+    ///
+    /// ```
+    /// use vec_reg_common::Regex;
+    ///
+    /// let is_fizz = Regex::satisfy_named("is_fizz", |x: &i32| x % 3 == 0);
+    /// let is_buzz = Regex::satisfy_named("is_buzz", |x: &i32| x % 5 == 0);
+    /// let reg = Regex::concat(is_fizz, Regex::repeat_n(is_buzz, 2)).compile();
+    /// let trace = reg.trace(&[3, 5, 7]).unwrap();
+    /// assert_eq!(trace.matched(), None);
+    /// assert_eq!(trace.furthest(), Some(0..2));
+    /// println!("{}", trace);
+    /// ```
+    pub fn trace(&self, input: &[I]) -> Result<MatchTrace, CompileError> {
+        if self.has_back_ref {
+            return Err(CompileError::BackRefNotSupported);
+        }
+
+        let search = Search {
+            slot_count: 2,
+            earliest: false,
+            anchored: self.anchored,
+            match_kind: self.match_kind,
+        };
+        let mut trace = MatchTrace::new();
+        let matched = run_vm_traced(&self.insts, input, 0, search, Some(&mut trace));
+        trace.matched = matched.and_then(|slots| Some(slots[0]?..slots[1]?));
+
+        Ok(trace)
+    }
+
+    /// Returns the engine used to execute the compiled program.
+    pub fn engine(&self) -> Engine {
+        self.engine
+    }
+
+    /// Returns where matches must start and end.
+    pub fn anchored(&self) -> Anchored {
+        self.anchored
+    }
+
+    /// Returns which match is reported.
+    pub fn match_kind(&self) -> MatchKind {
+        self.match_kind
+    }
+
+    /// Returns the number of capture groups, including the 0th capture for the entire match.
+    pub fn captures_len(&self) -> usize {
+        self.group_names.len()
+    }
+
+    /// Returns an iterator over the capture names, indexed by the group index.
+    /// Unnamed groups, including the 0th capture, yield None.
+    pub fn capture_names(&self) -> impl Iterator<Item = Option<&str>> + '_ {
+        self.group_names.iter().map(|name| name.as_deref())
+    }
+
+    /// Returns the compiled program as text, one instruction per line with its pc.
+    ///
+    /// Predicates are shown by their labels and capture groups by their names.
+    ///
+    /// ```
+    /// use vec_reg_common::Regex;
+    ///
+    /// let reg = Regex::satisfy_named("is_fizz", |x: &i32| x % 3 == 0).compile();
+    /// assert_eq!(
+    ///     reg.program_listing(),
+    ///     "   0  save open 0\n   1  check [is_fizz]\n   2  save close 0\n   3  match\n"
+    /// );
+    /// ```
+    pub fn program_listing(&self) -> String {
+        super::render::listing(&self.insts)
+    }
+
+    /// Returns the compiled program as a graph in DOT language, which can be rendered by Graphviz.
+    ///
+    /// Each instruction is a node labeled like `program_listing`, and edges of `Split` are labeled
+    /// by their priority. Sub-programs of look-around are drawn as dashed clusters.
+    pub fn to_dot(&self) -> String {
+        super::render::dot(&self.insts)
+    }
+}
+
+impl<I> Clone for CompiledRegexInVm<I> {
+    fn clone(&self) -> Self {
+        Self {
+            insts: self.insts.clone(),
+            group_names: self.group_names.clone(),
+            named_capture_index: self.named_capture_index.clone(),
+            engine: self.engine,
+            anchored: self.anchored,
+            match_kind: self.match_kind,
+            has_back_ref: self.has_back_ref,
+            backtrack_limit: self.backtrack_limit,
+        }
+    }
+}
+
+impl<I> std::fmt::Debug for CompiledRegexInVm<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompiledRegexInVm")
+            .field("insts", &self.insts)
+            .field("group_names", &self.group_names)
+            .field("engine", &self.engine)
+            .field("anchored", &self.anchored)
+            .field("match_kind", &self.match_kind)
+            .field("backtrack_limit", &self.backtrack_limit)
+            .finish()
+    }
+}
+
+impl<I> CompiledRegex<I> for CompiledRegexInVm<I> {
+    fn is_match_at(&self, input: &[I], start: usize) -> bool {
+        matches!(self.run(input, start, 0, true), Ok(Some(_)))
+    }
+
+    fn is_full_match(&self, input: &[I]) -> bool {
+        matches!(
+            self.run_anchored(input, 0, 0, true, Anchored::Both),
+            Ok(Some(_))
+        )
+    }
+
+    fn find_at<'a>(&self, input: &'a [I], start: usize) -> Option<Match<'a, I>> {
+        self.try_find_at(input, start).ok().flatten()
+    }
+
+    fn captures_at<'a>(&self, input: &'a [I], start: usize) -> Option<Captures<'a, I>> {
+        self.try_captures_at(input, start).ok().flatten()
+    }
+}
+
+impl<I> CompiledRegexInVm<I> {
+    /// Like `find`, but returns an error instead of no match if the backtracker gives up
+    /// a regex with back references at the limit set by `CompileOptions::backtrack_limit`.
+    ///
+    /// ```
+    /// use vec_reg_common::{CompileOptions, MatchError, Regex};
+    ///
+    /// let reg = Regex::concat(Regex::group(Regex::any()), Regex::back_ref(1))
+    ///     .try_compile_with(&CompileOptions::new().backtrack_limit(3))
+    ///     .unwrap();
+    /// assert_eq!(reg.try_find(&[1, 1]).unwrap().unwrap().range(), 0..2);
+    /// assert_eq!(
+    ///     reg.try_find(&[1, 2, 3, 4]).unwrap_err(),
+    ///     MatchError::BacktrackLimitExceeded { limit: 3 }
+    /// );
+    /// ```
+    pub fn try_find<'a>(&self, input: &'a [I]) -> Result<Option<Match<'a, I>>, MatchError> {
+        self.try_find_at(input, 0)
+    }
+
+    /// Like `captures`, but returns an error instead of no match if the backtracker gives up
+    /// a regex with back references at the limit set by `CompileOptions::backtrack_limit`.
+    pub fn try_captures<'a>(&self, input: &'a [I]) -> Result<Option<Captures<'a, I>>, MatchError> {
+        self.try_captures_at(input, 0)
+    }
+
+    fn try_find_at<'a>(
+        &self,
+        input: &'a [I],
+        start: usize,
+    ) -> Result<Option<Match<'a, I>>, MatchError> {
+        let slots = match self.run(input, start, 2, false)? {
+            Some(slots) => slots,
+            None => return Ok(None),
+        };
+        match (slots[0], slots[1]) {
+            (Some(start), Some(end)) => Ok(Some(Match { input, start, end })),
+            _ => panic!("Unexpected missing 0th capture."),
+        }
+    }
+
+    fn try_captures_at<'a>(
+        &self,
+        input: &'a [I],
+        start: usize,
+    ) -> Result<Option<Captures<'a, I>>, MatchError> {
+        let slots = match self.run(input, start, self.group_names.len() * 2, false)? {
+            Some(slots) => slots,
+            None => return Ok(None),
+        };
+        let capture_locations = slots
+            .chunks(2)
+            .map(|slot| match (slot[0], slot[1]) {
+                (Some(start), Some(end)) => Some(CaptureLocation { start, end }),
+                _ => None,
             })
-        } else {
-            None
+            .collect();
+        Ok(Some(Captures {
+            input,
+            capture_locations,
+            named_capture_index: self.named_capture_index.clone(),
+        }))
+    }
+}
+
+/// Regex validated to be compilable, wrapped in the 0th group capturing the entire match.
+pub(super) struct Pattern<I> {
+    regex: Regex<I>,
+    groups: GroupTable,
+    /// Number of instructions the pattern compiles to.
+    pub size: usize,
+}
+
+impl<I> Pattern<I> {
+    pub fn new(reg: Regex<I>) -> Result<Self, CompileError> {
+        // Wrapping given regex R in `(R)` to capture the entire match as the 0th group.
+        // Engines search from each position, so no `.*?` prefix is needed for partial matching.
+        let regex = Regex::Group(reg.into());
+        let mut groups = GroupTable::default();
+        collect_groups(&regex, &mut groups)?;
+        let size = validate_regex(&regex, &groups, false)?.saturating_add(1);
+
+        Ok(Self {
+            regex,
+            groups,
+            size,
+        })
+    }
+
+    /// Names of all groups by index, including groups that never compile to instructions
+    /// because they are repeated zero times.
+    pub fn group_names(&self) -> Vec<Option<String>> {
+        let mut group_names = vec![None; self.groups.len];
+        for (name, &group_index) in self.groups.named_indices.iter() {
+            group_names[group_index] = Some(name.to_owned());
         }
+
+        group_names
+    }
+
+    /// Compile into instructions placed from `start_pc`, ending with `Inst::Match`.
+    /// Returns the instructions and the indices of named groups.
+    pub fn compile(self, start_pc: PC) -> (Vec<Inst<I>>, HashMap<String, GroupIndex>) {
+        let (mut insts, _, _) =
+            _compile_regex(&self.regex, start_pc, 0, &self.groups.named_indices);
+        insts.push(Inst::Match);
+
+        (insts, self.groups.named_indices)
     }
 }
 
-pub fn compile_regex_to_vm_insts<I>(reg: &Regex<I>) -> Vec<Inst<I>> {
-    let (mut insts, _, _) = _compile_regex(reg, 0, 0);
+pub fn compile_regex_to_vm_insts<I>(
+    reg: &Regex<I>,
+    named_group_indices: &HashMap<String, GroupIndex>,
+) -> Vec<Inst<I>> {
+    let (mut insts, _, _) = _compile_regex(reg, 0, 0, named_group_indices);
     insts.push(Inst::Match);
 
     insts
 }
 
+#[derive(Default)]
+struct GroupTable {
+    named_indices: HashMap<String, GroupIndex>,
+    len: usize,
+}
+
+// Assign group indices in the same order as the compiler does.
+// Every copy of a repetition shares the same indices, and groups inside look-around don't capture.
+fn collect_groups<I>(reg: &Regex<I>, groups: &mut GroupTable) -> Result<(), CompileError> {
+    match reg {
+        Regex::Group(r) => {
+            groups.len += 1;
+            collect_groups(r, groups)
+        }
+        Regex::NamedGroup(name, r) => {
+            if groups
+                .named_indices
+                .insert(name.to_owned(), groups.len)
+                .is_some()
+            {
+                return Err(CompileError::DuplicateGroupName(name.to_owned()));
+            }
+            groups.len += 1;
+            collect_groups(r, groups)
+        }
+        Regex::Concat(r, s) | Regex::Or(r, s) => {
+            collect_groups(r, groups)?;
+            collect_groups(s, groups)
+        }
+        Regex::NonCapturingGroup(r)
+        | Regex::ZeroOrOne(r, _)
+        | Regex::Repeat0(r, _)
+        | Regex::Repeat1(r, _)
+        | Regex::RepeatN(r, _)
+        | Regex::RepeatMinMax(r, _, _, _) => collect_groups(r, groups),
+        _ => Ok(()),
+    }
+}
+
+// Check the regex is compilable and returns the number of instructions it compiles to,
+// without expanding repetitions.
+fn validate_regex<I>(
+    reg: &Regex<I>,
+    groups: &GroupTable,
+    in_look_around: bool,
+) -> Result<usize, CompileError> {
+    let size = match reg {
+        Regex::Begin
+        | Regex::End
+        | Regex::Satisfy(_, _)
+        | Regex::NotSatisfy(_, _)
+        | Regex::SatisfyPair(_, _) => 1,
+        Regex::Concat(r, s) => validate_regex(r, groups, in_look_around)?
+            .saturating_add(validate_regex(s, groups, in_look_around)?),
+        Regex::Group(r) | Regex::NamedGroup(_, r) => {
+            validate_regex(r, groups, in_look_around)?.saturating_add(2)
+        }
+        Regex::NonCapturingGroup(r) => validate_regex(r, groups, in_look_around)?,
+        Regex::Or(r, s) => validate_regex(r, groups, in_look_around)?
+            .saturating_add(validate_regex(s, groups, in_look_around)?)
+            .saturating_add(2),
+        Regex::ZeroOrOne(r, _) => validate_regex(r, groups, in_look_around)?.saturating_add(1),
+        Regex::Repeat0(r, _) => validate_regex(r, groups, in_look_around)?.saturating_add(2),
+        Regex::Repeat1(r, _) => validate_regex(r, groups, in_look_around)?.saturating_add(1),
+        Regex::RepeatN(r, n) => {
+            let r_size = validate_regex(r, groups, in_look_around)?;
+            r_size.saturating_mul(*n).max(1)
+        }
+        Regex::RepeatMinMax(r, n, m, _) => {
+            let r_size = validate_regex(r, groups, in_look_around)?;
+            match m {
+                Some(m) if m < n => {
+                    return Err(CompileError::InvalidRepetition { min: *n, max: *m });
+                }
+                Some(m) => r_size
+                    .saturating_mul(*n)
+                    .saturating_add(r_size.saturating_add(1).saturating_mul(m - n))
+                    .max(1),
+                None if *n == 0 => r_size.saturating_add(2),
+                None => r_size.saturating_mul(*n).saturating_add(1),
+            }
+        }
+        Regex::LookAhead(r)
+        | Regex::NegativeLookAhead(r)
+        | Regex::LookBehind(r)
+        | Regex::NegativeLookBehind(r) => validate_regex(r, groups, true)?.saturating_add(2),
+        Regex::BackRef(group_index, _) => {
+            // 0th group is the entire match, which never closes before the back reference.
+            if *group_index == 0 || *group_index >= groups.len {
+                return Err(CompileError::UnknownGroup(*group_index));
+            }
+            if in_look_around {
+                return Err(CompileError::BackRefInLookAround);
+            }
+            1
+        }
+        Regex::NamedBackRef(name, _) => {
+            if !groups.named_indices.contains_key(name) {
+                return Err(CompileError::UnknownGroupName(name.to_owned()));
+            }
+            if in_look_around {
+                return Err(CompileError::BackRefInLookAround);
+            }
+            1
+        }
+    };
+
+    Ok(size)
+}
+
 fn _compile_regex<I>(
     reg: &Regex<I>,
     start_pc: PC,
     next_group_index: GroupIndex,
+    named_group_indices: &HashMap<String, GroupIndex>,
 ) -> (Vec<Inst<I>>, PC, GroupIndex) {
     let mut insts = vec![];
     let end_pc;
@@ -118,19 +568,24 @@ fn _compile_regex<I>(
             insts.push(Inst::End);
             end_pc = start_pc;
         }
-        Regex::Satisfy(f) => {
-            insts.push(Inst::Check(f.clone()));
+        Regex::Satisfy(f, label) => {
+            insts.push(Inst::Check(Predicate::Value(f.clone()), label.clone()));
+            end_pc = start_pc;
+        }
+        Regex::NotSatisfy(f, label) => {
+            let label = label.as_ref().map(|label| format!("^{}", label));
+            insts.push(Inst::Check(Predicate::Value(f.clone()), label));
             end_pc = start_pc;
         }
-        Regex::NotSatisfy(f) => {
-            insts.push(Inst::Check(f.clone()));
+        Regex::SatisfyPair(f, label) => {
+            insts.push(Inst::Check(Predicate::Pair(f.clone()), label.clone()));
             end_pc = start_pc;
         }
         Regex::Concat(r, s) => {
             let (r_insts, r_end_pc, r_next_group_index) =
-                _compile_regex(r, start_pc, next_group_index);
+                _compile_regex(r, start_pc, next_group_index, named_group_indices);
             let (s_insts, s_end_pc, s_next_group_index) =
-                _compile_regex(s, r_end_pc + 1, r_next_group_index);
+                _compile_regex(s, r_end_pc + 1, r_next_group_index, named_group_indices);
             insts.extend(r_insts);
             insts.extend(s_insts);
             end_pc = s_end_pc;
@@ -140,7 +595,7 @@ fn _compile_regex<I>(
             insts.push(Inst::SaveOpen(next_group_index));
             let r_start_pc = start_pc + 1;
             let (r_insts, r_end_pc, r_next_group_index) =
-                _compile_regex(r, r_start_pc, next_group_index + 1);
+                _compile_regex(r, r_start_pc, next_group_index + 1, named_group_indices);
             insts.extend(r_insts);
             insts.push(Inst::SaveClose(next_group_index));
             end_pc = r_end_pc + 1;
@@ -150,7 +605,7 @@ fn _compile_regex<I>(
             insts.push(Inst::SaveNamedOpen(name.to_owned(), next_group_index));
             let r_start_pc = start_pc + 1;
             let (r_insts, r_end_pc, r_next_group_index) =
-                _compile_regex(r, r_start_pc, next_group_index + 1);
+                _compile_regex(r, r_start_pc, next_group_index + 1, named_group_indices);
             insts.extend(r_insts);
             insts.push(Inst::SaveNamedClose(name.to_owned(), next_group_index));
             end_pc = r_end_pc + 1;
@@ -159,7 +614,7 @@ fn _compile_regex<I>(
         Regex::NonCapturingGroup(r) => {
             let r_start_pc = start_pc;
             let (r_insts, r_end_pc, r_next_group_index) =
-                _compile_regex(r, r_start_pc, next_group_index);
+                _compile_regex(r, r_start_pc, next_group_index, named_group_indices);
             insts.extend(r_insts);
             end_pc = r_end_pc;
             new_next_group_index = r_next_group_index;
@@ -167,11 +622,11 @@ fn _compile_regex<I>(
         Regex::Or(r, s) => {
             let r_start_pc = start_pc + 1;
             let (r_insts, r_end_pc, r_next_group_index) =
-                _compile_regex(r, r_start_pc, next_group_index);
+                _compile_regex(r, r_start_pc, next_group_index, named_group_indices);
             let jmp_inst_pc = r_end_pc + 1;
             let s_start_pc = jmp_inst_pc + 1;
             let (s_insts, s_end_pc, s_next_group_index) =
-                _compile_regex(s, s_start_pc, r_next_group_index);
+                _compile_regex(s, s_start_pc, r_next_group_index, named_group_indices);
             end_pc = s_end_pc;
 
             insts.push(Inst::Split(r_start_pc, s_start_pc));
@@ -183,7 +638,7 @@ fn _compile_regex<I>(
         Regex::ZeroOrOne(r, greedy) => {
             let r_start_pc = start_pc + 1;
             let (r_insts, r_end_pc, r_next_group_index) =
-                _compile_regex(r, r_start_pc, next_group_index);
+                _compile_regex(r, r_start_pc, next_group_index, named_group_indices);
             end_pc = r_end_pc;
 
             if *greedy {
@@ -197,7 +652,7 @@ fn _compile_regex<I>(
         Regex::Repeat0(r, greedy) => {
             let r_start_pc = start_pc + 1;
             let (r_insts, r_end_pc, r_next_group_index) =
-                _compile_regex(r, r_start_pc, next_group_index);
+                _compile_regex(r, r_start_pc, next_group_index, named_group_indices);
             let jmp_inst_pc = r_end_pc + 1;
             end_pc = jmp_inst_pc;
 
@@ -212,7 +667,7 @@ fn _compile_regex<I>(
         }
         Regex::Repeat1(r, greedy) => {
             let (r_insts, r_end_pc, r_next_group_index) =
-                _compile_regex(r, start_pc, next_group_index);
+                _compile_regex(r, start_pc, next_group_index, named_group_indices);
             end_pc = r_end_pc + 1;
 
             insts.extend(r_insts);
@@ -224,65 +679,157 @@ fn _compile_regex<I>(
             new_next_group_index = r_next_group_index
         }
         Regex::RepeatN(r, n) => {
-            let expanded_r = expand_repeat_n(r.clone(), *n);
-            let (r_insts, r_end_pc, r_next_group_index) =
-                _compile_regex(&expanded_r, start_pc, next_group_index);
+            let expanded_regs = expand_repeat_n(r.clone(), *n);
+            let (r_insts, r_end_pc, r_next_group_index) = _compile_repetition(
+                r,
+                &expanded_regs,
+                start_pc,
+                next_group_index,
+                named_group_indices,
+            );
             insts.extend(r_insts);
             end_pc = r_end_pc;
-            new_next_group_index = r_next_group_index
+            new_next_group_index = r_next_group_index;
         }
         Regex::RepeatMinMax(r, n, m, greedy) => {
-            let expanded_r = expand_repeat_min_max(r.clone(), *n, m, *greedy);
-            let (r_insts, r_end_pc, r_next_group_index) =
-                _compile_regex(&expanded_r, start_pc, next_group_index);
+            let expanded_regs = expand_repeat_min_max(r.clone(), *n, m, *greedy);
+            let (r_insts, r_end_pc, r_next_group_index) = _compile_repetition(
+                r,
+                &expanded_regs,
+                start_pc,
+                next_group_index,
+                named_group_indices,
+            );
             insts.extend(r_insts);
             end_pc = r_end_pc;
             new_next_group_index = r_next_group_index;
         }
+        Regex::LookAhead(r) => {
+            insts.push(Inst::LookAhead(
+                compile_regex_to_vm_insts(r, named_group_indices).into(),
+                false,
+            ));
+            end_pc = start_pc;
+        }
+        Regex::NegativeLookAhead(r) => {
+            insts.push(Inst::LookAhead(
+                compile_regex_to_vm_insts(r, named_group_indices).into(),
+                true,
+            ));
+            end_pc = start_pc;
+        }
+        Regex::LookBehind(r) => {
+            let reversed_r = reverse_regex(r);
+            insts.push(Inst::LookBehind(
+                compile_regex_to_vm_insts(&reversed_r, named_group_indices).into(),
+                false,
+            ));
+            end_pc = start_pc;
+        }
+        Regex::NegativeLookBehind(r) => {
+            let reversed_r = reverse_regex(r);
+            insts.push(Inst::LookBehind(
+                compile_regex_to_vm_insts(&reversed_r, named_group_indices).into(),
+                true,
+            ));
+            end_pc = start_pc;
+        }
+        Regex::BackRef(group_index, eq) => {
+            insts.push(Inst::BackRef(*group_index, eq.clone()));
+            end_pc = start_pc;
+        }
+        Regex::NamedBackRef(name, eq) => {
+            insts.push(Inst::BackRef(named_group_indices[name], eq.clone()));
+            end_pc = start_pc;
+        }
     }
 
     (insts, end_pc, new_next_group_index)
 }
 
-fn expand_repeat_n<I>(r: Rc<Regex<I>>, n: usize) -> Rc<Regex<I>> {
-    let regs = vec![r; n];
-    concat_regex_list(&regs)
+// Compile copies of the repeated regex in sequence. Every copy shares the same group indices.
+fn _compile_repetition<I>(
+    reg: &Regex<I>,
+    regs: &[Arc<Regex<I>>],
+    start_pc: PC,
+    next_group_index: GroupIndex,
+    named_group_indices: &HashMap<String, GroupIndex>,
+) -> (Vec<Inst<I>>, PC, GroupIndex) {
+    if regs.is_empty() {
+        // Zero repetition matches empty, so just continue to the next instruction.
+        // Groups inside still take their indices, so later groups are numbered as in `collect_groups`.
+        let mut groups = GroupTable::default();
+        let _ = collect_groups(reg, &mut groups);
+        return (
+            vec![Inst::Jmp(start_pc + 1)],
+            start_pc,
+            next_group_index + groups.len,
+        );
+    }
+
+    let mut insts = vec![];
+    let mut end_pc = start_pc;
+    let mut new_next_group_index = next_group_index;
+    for r in regs.iter() {
+        let r_start_pc = start_pc + insts.len();
+        let (r_insts, r_end_pc, r_next_group_index) =
+            _compile_regex(r, r_start_pc, next_group_index, named_group_indices);
+        insts.extend(r_insts);
+        end_pc = r_end_pc;
+        new_next_group_index = r_next_group_index;
+    }
+
+    (insts, end_pc, new_next_group_index)
+}
+
+// Build regex that matches the reversed sequence, so that look-behind can run backward from the current position.
+fn reverse_regex<I>(reg: &Arc<Regex<I>>) -> Arc<Regex<I>> {
+    let reversed = match reg.as_ref() {
+        Regex::Concat(r, s) => Regex::Concat(reverse_regex(s), reverse_regex(r)),
+        Regex::Group(r) => Regex::Group(reverse_regex(r)),
+        Regex::NamedGroup(name, r) => Regex::NamedGroup(name.to_owned(), reverse_regex(r)),
+        Regex::NonCapturingGroup(r) => Regex::NonCapturingGroup(reverse_regex(r)),
+        Regex::Or(r, s) => Regex::Or(reverse_regex(r), reverse_regex(s)),
+        Regex::ZeroOrOne(r, greedy) => Regex::ZeroOrOne(reverse_regex(r), *greedy),
+        Regex::Repeat0(r, greedy) => Regex::Repeat0(reverse_regex(r), *greedy),
+        Regex::Repeat1(r, greedy) => Regex::Repeat1(reverse_regex(r), *greedy),
+        Regex::RepeatN(r, n) => Regex::RepeatN(reverse_regex(r), *n),
+        Regex::RepeatMinMax(r, n, m, greedy) => {
+            Regex::RepeatMinMax(reverse_regex(r), *n, *m, *greedy)
+        }
+        // Single value predicates, anchors and nested assertions are the same in both direction.
+        _ => return reg.clone(),
+    };
+
+    Arc::new(reversed)
+}
+
+fn expand_repeat_n<I>(r: Arc<Regex<I>>, n: usize) -> Vec<Arc<Regex<I>>> {
+    vec![r; n]
 }
 
 fn expand_repeat_min_max<I>(
-    r: Rc<Regex<I>>,
+    r: Arc<Regex<I>>,
     n: usize,
     m: &Option<usize>,
     greedy: bool,
-) -> Rc<Regex<I>> {
+) -> Vec<Arc<Regex<I>>> {
     let mut regs = vec![];
     if let Some(m) = m {
         for _ in 1..=n {
             regs.push(r.clone());
         }
         for _ in 1..=(*m - n) {
-            regs.push(Rc::new(Regex::ZeroOrOne(r.clone(), greedy)));
+            regs.push(Arc::new(Regex::ZeroOrOne(r.clone(), greedy)));
         }
+    } else if n == 0 {
+        regs.push(Arc::new(Regex::Repeat0(r, greedy)));
     } else {
         for _ in 1..=(n - 1) {
             regs.push(r.clone());
         }
-        regs.push(Rc::new(Regex::Repeat1(r, greedy)));
-    }
-
-    concat_regex_list(&regs)
-}
-
-fn concat_regex_list<I>(regs: &[Rc<Regex<I>>]) -> Rc<Regex<I>> {
-    let n = regs.len();
-    if n == 1 {
-        return regs[0].clone();
-    }
-
-    let mut reg = regs[0].clone();
-    for r in regs.iter().skip(1) {
-        reg = Rc::new(Regex::Concat(reg, r.clone()));
+        regs.push(Arc::new(Regex::Repeat1(r, greedy)));
     }
 
-    reg
+    regs
 }
@@ -1,15 +1,49 @@
-use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::{EqFn, PairFn};
 
 pub type PC = usize;
-#[allow(dead_code)]
 pub type SP = usize;
 pub type GroupIndex = usize;
 pub type GroupName = String;
 
+pub enum Predicate<I> {
+    // Predicate on a single value.
+    Value(Arc<dyn Fn(&I) -> bool + Send + Sync + 'static>),
+    // Predicate on the previous value and the current value.
+    Pair(PairFn<I>),
+}
+
+impl<I> Predicate<I> {
+    /// Test the value at `index` of the input. Pair predicate never matches the first value.
+    pub fn test(&self, input: &[I], index: SP) -> bool {
+        let prev = index.checked_sub(1).map(|prev_index| &input[prev_index]);
+        self.test_with(prev, &input[index])
+    }
+
+    /// Test the value preceded by `prev`, which is None for the first value.
+    pub fn test_with(&self, prev: Option<&I>, value: &I) -> bool {
+        match self {
+            Predicate::Value(f) => f(value),
+            Predicate::Pair(f) => prev.is_some_and(|prev| f(prev, value)),
+        }
+    }
+}
+
+impl<I> Clone for Predicate<I> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Value(f) => Self::Value(f.clone()),
+            Self::Pair(f) => Self::Pair(f.clone()),
+        }
+    }
+}
+
 pub enum Inst<I> {
     Begin,
     End,
-    Check(Rc<dyn Fn(&I) -> bool + 'static>),
+    // Predicate on the value at the current position, and its label if any.
+    Check(Predicate<I>, Option<String>),
     Match,
     Jmp(PC),
     Split(PC, PC),
@@ -17,6 +51,11 @@ pub enum Inst<I> {
     SaveClose(GroupIndex),
     SaveNamedOpen(GroupName, GroupIndex),
     SaveNamedClose(GroupName, GroupIndex),
+    // Sub-program run forward from the current position, and whether the assertion is negated.
+    LookAhead(Arc<Vec<Inst<I>>>, bool),
+    // Sub-program of the reversed regex run backward from the current position, and whether the assertion is negated.
+    LookBehind(Arc<Vec<Inst<I>>>, bool),
+    BackRef(GroupIndex, EqFn<I>),
 }
 
 impl<I> std::fmt::Debug for Inst<I> {
@@ -24,7 +63,14 @@ impl<I> std::fmt::Debug for Inst<I> {
         match self {
             Self::Begin => write!(f, "Begin"),
             Self::End => write!(f, "End"),
-            Self::Check(_arg0) => f.debug_tuple("Check").field(&"#<fn>").finish(),
+            Self::Check(Predicate::Value(_), label) => f
+                .debug_tuple("Check")
+                .field(&label.as_deref().unwrap_or("<fn>"))
+                .finish(),
+            Self::Check(Predicate::Pair(_), label) => f
+                .debug_tuple("CheckPair")
+                .field(&label.as_deref().unwrap_or("<fn>"))
+                .finish(),
             Self::Match => write!(f, "Match"),
             Self::Jmp(arg0) => f.debug_tuple("Jmp").field(arg0).finish(),
             Self::Split(arg0, arg1) => f.debug_tuple("Split").field(arg0).field(arg1).finish(),
@@ -40,6 +86,17 @@ impl<I> std::fmt::Debug for Inst<I> {
                 .field(name)
                 .field(idx)
                 .finish(),
+            Self::LookAhead(insts, negate) => f
+                .debug_tuple("LookAhead")
+                .field(insts)
+                .field(negate)
+                .finish(),
+            Self::LookBehind(insts, negate) => f
+                .debug_tuple("LookBehind")
+                .field(insts)
+                .field(negate)
+                .finish(),
+            Self::BackRef(idx, _eq) => f.debug_tuple("BackRef").field(idx).finish(),
         }
     }
 }
@@ -49,7 +106,7 @@ impl<I> Clone for Inst<I> {
         match self {
             Self::Begin => Self::Begin,
             Self::End => Self::End,
-            Self::Check(arg0) => Self::Check(arg0.clone()),
+            Self::Check(arg0, label) => Self::Check(arg0.clone(), label.clone()),
             Self::Match => Self::Match,
             Self::Jmp(arg0) => Self::Jmp(*arg0),
             Self::Split(arg0, arg1) => Self::Split(*arg0, *arg1),
@@ -57,6 +114,9 @@ impl<I> Clone for Inst<I> {
             Self::SaveClose(idx) => Self::SaveClose(*idx),
             Self::SaveNamedOpen(name, idx) => Self::SaveNamedOpen(name.clone(), *idx),
             Self::SaveNamedClose(name, idx) => Self::SaveNamedClose(name.clone(), *idx),
+            Self::LookAhead(insts, negate) => Self::LookAhead(insts.clone(), *negate),
+            Self::LookBehind(insts, negate) => Self::LookBehind(insts.clone(), *negate),
+            Self::BackRef(idx, eq) => Self::BackRef(*idx, eq.clone()),
         }
     }
 }
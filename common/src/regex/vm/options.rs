@@ -0,0 +1,106 @@
+/// The default size limit of the compiled program, in number of instructions.
+pub const DEFAULT_SIZE_LIMIT: usize = 1 << 20;
+
+/// The default number of paths the backtracker may follow in a search of a regex with back references.
+pub const DEFAULT_BACKTRACK_LIMIT: usize = 1_000_000;
+
+/// The engine used to execute the compiled program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    /// Pike VM, which runs in time proportional to program size times input length.
+    PikeVm,
+    /// Bounded backtracker, which remembers visited instruction and position pairs like RE2's BitState.
+    ///
+    /// It is usually faster for small programs over short inputs,
+    /// but needs memory proportional to program size times input length.
+    /// Without back references, searches whose memory would exceed a fixed cap run on the Pike VM instead.
+    /// Back references are only supported by this engine,
+    /// and a search with them gives up after following `CompileOptions::backtrack_limit` paths.
+    Backtrack,
+}
+
+/// Where a match must start and end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchored {
+    /// A match may start and end at any position.
+    No,
+    /// A match must start at the position the search starts.
+    Start,
+    /// A match must start at the position the search starts, and end at the end of the input.
+    Both,
+}
+
+/// Which match is reported among the matches starting at the leftmost position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    /// The match found first by following greedy and non-greedy preferences, like Perl or the `regex` crate.
+    LeftmostFirst,
+    /// The longest match regardless of greedy and non-greedy preferences, like POSIX.
+    LeftmostLongest,
+}
+
+/// Options to configure how a regex is compiled.
+#[derive(Debug, Clone)]
+pub struct CompileOptions {
+    pub(crate) size_limit: usize,
+    pub(crate) engine: Option<Engine>,
+    pub(crate) anchored: Anchored,
+    pub(crate) match_kind: MatchKind,
+    pub(crate) backtrack_limit: usize,
+}
+
+impl CompileOptions {
+    /// Build options with default configuration.
+    pub fn new() -> Self {
+        Self {
+            size_limit: DEFAULT_SIZE_LIMIT,
+            engine: None,
+            anchored: Anchored::No,
+            match_kind: MatchKind::LeftmostFirst,
+            backtrack_limit: DEFAULT_BACKTRACK_LIMIT,
+        }
+    }
+
+    /// Set the approximate size limit, in number of instructions, of the compiled program.
+    pub fn size_limit(mut self, limit: usize) -> Self {
+        self.size_limit = limit;
+        self
+    }
+
+    /// Set the engine to execute the compiled program.
+    ///
+    /// By default, the backtracker is used if the regex contains back references, and the Pike VM otherwise.
+    pub fn engine(mut self, engine: Engine) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
+    /// Set where matches must start and end. Defaults to `Anchored::No`.
+    pub fn anchored(mut self, anchored: Anchored) -> Self {
+        self.anchored = anchored;
+        self
+    }
+
+    /// Set which match is reported. Defaults to `MatchKind::LeftmostFirst`.
+    pub fn match_kind(mut self, match_kind: MatchKind) -> Self {
+        self.match_kind = match_kind;
+        self
+    }
+
+    /// Set the number of paths the backtracker may follow in a search of a regex with back references.
+    /// Defaults to `DEFAULT_BACKTRACK_LIMIT`.
+    ///
+    /// Back references keep the backtracker from skipping visited states, so its running time can be exponential.
+    /// A search exceeding the limit reports no match, and `CompiledRegexInVm::try_find` and
+    /// `CompiledRegexInVm::try_captures` return `MatchError::BacktrackLimitExceeded`.
+    pub fn backtrack_limit(mut self, limit: usize) -> Self {
+        self.backtrack_limit = limit;
+        self
+    }
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
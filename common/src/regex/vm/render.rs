@@ -0,0 +1,114 @@
+use std::fmt::Write;
+
+use super::inst::{Inst, Predicate, PC};
+
+/// Render the program as lines of `pc  instruction`.
+/// Sub-programs of look-around are listed under their instruction with deeper indent.
+pub(super) fn listing<I>(insts: &[Inst<I>]) -> String {
+    let mut out = String::new();
+    write_listing(&mut out, insts, 0);
+
+    out
+}
+
+fn write_listing<I>(out: &mut String, insts: &[Inst<I>], depth: usize) {
+    let indent = "    ".repeat(depth);
+    for (pc, inst) in insts.iter().enumerate() {
+        writeln!(out, "{}{:>4}  {}", indent, pc, describe(inst)).unwrap();
+        if let Inst::LookAhead(look_insts, _) | Inst::LookBehind(look_insts, _) = inst {
+            write_listing(out, look_insts, depth + 1);
+        }
+    }
+}
+
+/// Render the program as a graph in DOT language.
+///
+/// Each instruction is a node, and edges are transitions between them.
+/// Edges of `Split` are labeled by their priority, and sub-programs of look-around are drawn as clusters.
+pub(super) fn dot<I>(insts: &[Inst<I>]) -> String {
+    let mut out = String::new();
+    writeln!(out, "digraph program {{").unwrap();
+    writeln!(out, "    rankdir=LR;").unwrap();
+    writeln!(out, "    node [shape=box];").unwrap();
+    write_dot(&mut out, insts, "n");
+    writeln!(out, "}}").unwrap();
+
+    out
+}
+
+fn write_dot<I>(out: &mut String, insts: &[Inst<I>], prefix: &str) {
+    for (pc, inst) in insts.iter().enumerate() {
+        let id = format!("{}{}", prefix, pc);
+        let label = escape(&format!("{}: {}", pc, describe(inst)));
+        match inst {
+            Inst::Match => {
+                writeln!(out, "    {} [label=\"{}\", shape=doublecircle];", id, label).unwrap()
+            }
+            _ => writeln!(out, "    {} [label=\"{}\"];", id, label).unwrap(),
+        }
+
+        let edge = |out: &mut String, to: PC, attrs: &str| {
+            writeln!(out, "    {} -> {}{}{};", id, prefix, to, attrs).unwrap();
+        };
+        match inst {
+            Inst::Match => {}
+            Inst::Jmp(x) => edge(out, *x, ""),
+            Inst::Split(x, y) => {
+                edge(out, *x, " [label=\"1\"]");
+                edge(out, *y, " [label=\"2\"]");
+            }
+            Inst::LookAhead(look_insts, _) | Inst::LookBehind(look_insts, _) => {
+                let look_prefix = format!("{}_", id);
+                writeln!(out, "    subgraph cluster_{} {{", id).unwrap();
+                writeln!(out, "    style=dashed;").unwrap();
+                write_dot(out, look_insts, &look_prefix);
+                writeln!(out, "    }}").unwrap();
+                writeln!(out, "    {} -> {}0 [style=dashed];", id, look_prefix).unwrap();
+                edge(out, pc + 1, "");
+            }
+            _ => edge(out, pc + 1, ""),
+        }
+    }
+}
+
+// Describe the instruction with predicate labels and group names.
+fn describe<I>(inst: &Inst<I>) -> String {
+    match inst {
+        Inst::Begin => "begin".to_owned(),
+        Inst::End => "end".to_owned(),
+        Inst::Check(Predicate::Value(_), label) => {
+            format!("check {}", predicate_syntax(label.as_deref(), "<fn>"))
+        }
+        Inst::Check(Predicate::Pair(_), label) => {
+            format!(
+                "check pair {}",
+                predicate_syntax(label.as_deref(), "<pair fn>")
+            )
+        }
+        Inst::Match => "match".to_owned(),
+        Inst::Jmp(x) => format!("jmp {}", x),
+        Inst::Split(x, y) => format!("split {}, {}", x, y),
+        Inst::SaveOpen(idx) => format!("save open {}", idx),
+        Inst::SaveClose(idx) => format!("save close {}", idx),
+        Inst::SaveNamedOpen(name, idx) => format!("save open {} \"{}\"", idx, name),
+        Inst::SaveNamedClose(name, idx) => format!("save close {} \"{}\"", idx, name),
+        Inst::LookAhead(_, false) => "look-ahead".to_owned(),
+        Inst::LookAhead(_, true) => "negative look-ahead".to_owned(),
+        Inst::LookBehind(_, false) => "look-behind".to_owned(),
+        Inst::LookBehind(_, true) => "negative look-behind".to_owned(),
+        Inst::BackRef(idx, _) => format!("back-ref {}", idx),
+    }
+}
+
+/// Show the predicate as in the pattern, like `[is_fizz]` or `.`.
+pub(super) fn predicate_syntax(label: Option<&str>, unlabeled: &str) -> String {
+    match label {
+        Some(".") => ".".to_owned(),
+        Some(label) => format!("[{}]", label),
+        None => format!("[{}]", unlabeled),
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
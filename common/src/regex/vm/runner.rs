@@ -1,183 +1,295 @@
-use std::{collections::HashMap, rc::Rc};
-
 pub use super::inst::Inst;
-use super::inst::{GroupIndex, PC, SP};
+use super::inst::{PC, SP};
+use super::options::{Anchored, MatchKind};
+use super::trace::{MatchTrace, PredicateTest, TraceStep, TraceThread};
+
+/// Saved positions of capture groups. Group `i` opens at slot `i * 2` and closes at slot `i * 2 + 1`.
+pub type Slots = Vec<Option<SP>>;
 
-pub struct Thread {
-    pub pc: PC,
-    pub saved: Rc<HashMap<usize, SP>>,
-    pub named_capture_index: Rc<HashMap<String, GroupIndex>>,
+/// How to run the program for a single search.
+#[derive(Debug, Clone, Copy)]
+pub struct Search {
+    /// Number of tracked slots, so that callers which don't need every capture group don't pay for them.
+    pub slot_count: usize,
+    /// Stop as soon as any thread reaches `Inst::Match`, which is enough to know whether the regex matches.
+    /// Otherwise the leftmost-first match is reported.
+    pub earliest: bool,
+    /// Where the match must start and end.
+    pub anchored: Anchored,
+    /// Which match is reported. `MatchKind::LeftmostLongest` needs slots of the 0th group.
+    pub match_kind: MatchKind,
 }
 
-// Define thread equality by PC.
-impl PartialEq for Thread {
-    fn eq(&self, other: &Self) -> bool {
-        self.pc == other.pc
-    }
+// Set of pcs which keeps insertion order, and can be cleared in constant time.
+pub(super) struct SparseSet {
+    pub dense: Vec<PC>,
+    sparse: Vec<usize>,
 }
 
-impl Eq for Thread {}
+impl SparseSet {
+    fn new(size: usize) -> Self {
+        Self {
+            dense: Vec::with_capacity(size),
+            sparse: vec![0; size],
+        }
+    }
 
-impl std::hash::Hash for Thread {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.pc.hash(state);
+    fn contains(&self, pc: PC) -> bool {
+        let i = self.sparse[pc];
+        i < self.dense.len() && self.dense[i] == pc
     }
-}
 
-impl Clone for Thread {
-    fn clone(&self) -> Self {
-        Thread {
-            pc: self.pc,
-            saved: self.saved.clone(),
-            named_capture_index: self.named_capture_index.clone(),
-        }
+    fn insert(&mut self, pc: PC) {
+        self.sparse[pc] = self.dense.len();
+        self.dense.push(pc);
+    }
+
+    pub fn clear(&mut self) {
+        self.dense.clear();
     }
 }
 
-struct ThreadPool {
-    seen_pc: Vec<bool>,
-    threads: Vec<Thread>,
+// Threads waiting for input at the same position, in priority order.
+// Saved positions of each thread are stored in a flat table indexed by its pc.
+pub(super) struct ThreadList {
+    pub set: SparseSet,
+    slot_table: Vec<Option<SP>>,
+    slot_count: usize,
 }
 
-impl ThreadPool {
-    pub fn new(size: usize) -> Self {
+impl ThreadList {
+    pub fn new(prog_size: usize, slot_count: usize) -> Self {
         Self {
-            seen_pc: vec![false; size],
-            threads: Vec::with_capacity(size),
+            set: SparseSet::new(prog_size),
+            slot_table: vec![None; prog_size * slot_count],
+            slot_count,
         }
     }
 
-    pub fn add_thread<I>(&mut self, insts: &[Inst<I>], th: Thread, sp: SP, end_of_input: bool) {
-        if self.seen_pc[th.pc] {
-            return;
-        }
+    pub fn slots(&self, pc: PC) -> &[Option<SP>] {
+        &self.slot_table[pc * self.slot_count..(pc + 1) * self.slot_count]
+    }
 
-        let mut stack = vec![th];
-        while let Some(mut th) = stack.pop() {
-            let active_inst = &insts[th.pc];
-            match active_inst {
-                Inst::Begin => {
-                    if sp == 0 {
-                        th.pc += 1;
-                        if !self.seen_pc[th.pc] {
-                            stack.push(th);
-                        }
-                    }
-                }
-                Inst::End => {
-                    if end_of_input {
-                        th.pc += 1;
-                        if !self.seen_pc[th.pc] {
-                            stack.push(th);
-                        }
-                    }
-                }
-                Inst::Jmp(x) => {
-                    th.pc = *x;
-                    if !self.seen_pc[th.pc] {
-                        stack.push(th);
-                    }
+    fn slots_mut(&mut self, pc: PC) -> &mut [Option<SP>] {
+        &mut self.slot_table[pc * self.slot_count..(pc + 1) * self.slot_count]
+    }
+}
+
+pub(super) enum Frame {
+    // Follow empty transitions from the pc.
+    Explore(PC),
+    // Restore saved position after exploring a lower priority alternative.
+    RestoreSlot(usize, Option<SP>),
+}
+
+// Follow empty transitions from `pc` at `sp`, and add threads waiting for input to `list`.
+// `slots` holds the saved positions of the thread being added, and is restored on return.
+fn add_thread<I>(
+    insts: &[Inst<I>],
+    list: &mut ThreadList,
+    pc: PC,
+    input: &[I],
+    sp: SP,
+    slots: &mut [Option<SP>],
+    stack: &mut Vec<Frame>,
+) {
+    let at_end = Some(sp == input.len());
+    add_thread_with_end(insts, list, pc, input, sp, at_end, slots, stack);
+}
+
+/// Same as `add_thread`, but whether `sp` is the end of input is given by `at_end`.
+///
+/// If `at_end` is None, the end of input is not known yet,
+/// so threads at `Inst::End` are added to `list` to wait until it is known.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn add_thread_with_end<I>(
+    insts: &[Inst<I>],
+    list: &mut ThreadList,
+    pc: PC,
+    input: &[I],
+    sp: SP,
+    at_end: Option<bool>,
+    slots: &mut [Option<SP>],
+    stack: &mut Vec<Frame>,
+) {
+    stack.push(Frame::Explore(pc));
+    while let Some(frame) = stack.pop() {
+        let mut pc = match frame {
+            Frame::Explore(pc) => pc,
+            Frame::RestoreSlot(slot, value) => {
+                slots[slot] = value;
+                continue;
+            }
+        };
+        loop {
+            // Every instruction is followed at most once per position,
+            // so that the higher priority thread wins and empty loops terminate.
+            if list.set.contains(pc) {
+                break;
+            }
+            list.set.insert(pc);
+
+            match &insts[pc] {
+                Inst::Begin if sp == 0 => pc += 1,
+                Inst::End if at_end == Some(true) => pc += 1,
+                Inst::Begin => break,
+                Inst::End if at_end.is_none() => {
+                    list.slots_mut(pc).copy_from_slice(slots);
+                    break;
                 }
+                Inst::End => break,
+                Inst::Jmp(x) => pc = *x,
                 Inst::Split(x, y) => {
-                    if !self.seen_pc[*y] {
-                        stack.push(Thread {
-                            pc: *y,
-                            saved: th.saved.clone(),
-                            named_capture_index: th.named_capture_index.clone(),
-                        });
-                    }
-                    if !self.seen_pc[*x] {
-                        th.pc = *x;
-                        stack.push(th);
-                    }
+                    stack.push(Frame::Explore(*y));
+                    pc = *x;
                 }
-                Inst::SaveOpen(group_index) => {
-                    th.pc += 1;
-                    if !self.seen_pc[th.pc] {
-                        let saved_mut = Rc::make_mut(&mut th.saved);
-                        saved_mut.insert(group_index * 2, sp);
-                        stack.push(th);
-                    }
+                Inst::SaveOpen(group_index) | Inst::SaveNamedOpen(_, group_index) => {
+                    save(slots, stack, group_index * 2, sp);
+                    pc += 1;
                 }
-                Inst::SaveClose(group_index) => {
-                    th.pc += 1;
-                    if !self.seen_pc[th.pc] {
-                        let saved_mut = Rc::make_mut(&mut th.saved);
-                        saved_mut.insert(group_index * 2 + 1, sp);
-
-                        stack.push(th);
-                    }
+                Inst::SaveClose(group_index) | Inst::SaveNamedClose(_, group_index) => {
+                    save(slots, stack, group_index * 2 + 1, sp);
+                    pc += 1;
                 }
-                Inst::SaveNamedOpen(name, group_index) => {
-                    th.pc += 1;
-                    if !self.seen_pc[th.pc] {
-                        let saved_mut = Rc::make_mut(&mut th.saved);
-                        saved_mut.insert(group_index * 2, sp);
-                        let named_capture_index_mut = Rc::make_mut(&mut th.named_capture_index);
-                        named_capture_index_mut.insert(name.clone(), *group_index);
-
-                        stack.push(th);
+                Inst::LookAhead(look_insts, negate) => {
+                    if run_look(look_insts, input, sp, false) == *negate {
+                        break;
                     }
+                    pc += 1;
                 }
-                Inst::SaveNamedClose(name, group_index) => {
-                    th.pc += 1;
-                    if !self.seen_pc[th.pc] {
-                        let saved_mut = Rc::make_mut(&mut th.saved);
-                        saved_mut.insert(group_index * 2 + 1, sp);
-                        let named_capture_index_mut = Rc::make_mut(&mut th.named_capture_index);
-                        named_capture_index_mut.insert(name.clone(), *group_index);
-
-                        stack.push(th);
+                Inst::LookBehind(look_insts, negate) => {
+                    if run_look(look_insts, input, sp, true) == *negate {
+                        break;
                     }
+                    pc += 1;
                 }
-                _ => {
-                    self.seen_pc[th.pc] = true;
-                    self.threads.push(th);
+                Inst::Check(_, _) | Inst::Match | Inst::BackRef(_, _) => {
+                    list.slots_mut(pc).copy_from_slice(slots);
+                    break;
                 }
             }
         }
     }
 }
 
-pub fn run_vm<I>(insts: &[Inst<I>], input: &[I]) -> Option<Thread> {
+// Save the position to the slot, unless the slot is not tracked.
+fn save(slots: &mut [Option<SP>], stack: &mut Vec<Frame>, slot: usize, sp: SP) {
+    if let Some(value) = slots.get_mut(slot) {
+        stack.push(Frame::RestoreSlot(slot, *value));
+        *value = Some(sp);
+    }
+}
+
+/// Run the program over `input`, starting the search at `start`, and returns saved positions of the match.
+///
+/// The program is anchored, and the engine starts it at every position from `start`
+/// until the match is decided, instead of running a compiled `.*?` prefix.
+/// Group `i` opens at slot `i * 2` and closes at slot `i * 2 + 1`.
+///
+/// Positions are always relative to the whole input, so `Inst::Begin` only matches at `0`
+/// even when the search starts later.
+pub fn run_vm<I>(insts: &[Inst<I>], input: &[I], start: SP, search: Search) -> Option<Slots> {
+    run_vm_traced(insts, input, start, search, None)
+}
+
+/// Same as `run_vm`, but records live threads and predicate tests at each position to `trace` if given.
+///
+/// `search.slot_count` must be at least 2 to record where each thread started.
+pub fn run_vm_traced<I>(
+    insts: &[Inst<I>],
+    input: &[I],
+    start: SP,
+    search: Search,
+    mut trace: Option<&mut MatchTrace>,
+) -> Option<Slots> {
+    if start > input.len() {
+        return None;
+    }
+
+    let slot_count = search.slot_count;
     let prog_size = insts.len();
-    let mut clist = ThreadPool::new(prog_size);
-    let mut sp = 0;
-    let mut end_of_input = sp == input.len();
-    clist.add_thread(
-        insts,
-        Thread {
-            pc: 0,
-            saved: Rc::new(HashMap::new()),
-            named_capture_index: Rc::new(HashMap::new()),
-        },
-        0,
-        end_of_input,
-    );
-
-    let mut matched_thread = None;
-    'outer: while sp <= input.len() {
-        end_of_input = sp == input.len();
-        let mut nlist = ThreadPool::new(prog_size);
-        for mut th in clist.threads.into_iter() {
-            match &insts[th.pc] {
-                Inst::Check(f) => {
-                    if !end_of_input {
-                        let i = &input[sp];
-                        if f(i) {
-                            th.pc += 1;
-                            nlist.add_thread(insts, th, sp + 1, sp + 1 == input.len());
-                        }
+    let mut clist = ThreadList::new(prog_size, slot_count);
+    let mut nlist = ThreadList::new(prog_size, slot_count);
+    let mut slots = vec![None; slot_count];
+    let mut stack = vec![];
+    let mut sp = start;
+
+    let mut matched: Option<Slots> = None;
+    while sp <= input.len() {
+        if matched.is_none() && (sp == start || search.anchored == Anchored::No) {
+            // Start a new search at each position until a match is found.
+            // It has the lowest priority, so the match found by earlier threads is the leftmost one.
+            slots.fill(None);
+            add_thread(insts, &mut clist, 0, input, sp, &mut slots, &mut stack);
+        } else if clist.set.dense.is_empty() {
+            // No thread can find or extend the match anymore.
+            break;
+        }
+
+        if let Some(trace) = trace.as_deref_mut() {
+            let threads = clist
+                .set
+                .dense
+                .iter()
+                .filter(|&&pc| matches!(insts[pc], Inst::Check(_, _) | Inst::Match))
+                .map(|&pc| TraceThread::new(&insts[pc], pc, clist.slots(pc)[0]))
+                .collect();
+            trace.steps.push(TraceStep::new(sp, threads));
+        }
+
+        let end_of_input = sp == input.len();
+        for &pc in clist.set.dense.iter() {
+            if let (MatchKind::LeftmostLongest, Some(found)) = (search.match_kind, &matched) {
+                // Threads starting after the match can't be the leftmost one.
+                if clist.slots(pc)[0] > found[0] {
+                    continue;
+                }
+            }
+            match &insts[pc] {
+                Inst::Check(predicate, _) if !end_of_input => {
+                    let result = predicate.test(input, sp);
+                    if let Some(step) = trace.as_deref_mut().and_then(|t| t.steps.last_mut()) {
+                        step.tests.push(PredicateTest::new(&insts[pc], pc, result));
                     }
+                    if !result {
+                        continue;
+                    }
+                    slots.copy_from_slice(clist.slots(pc));
+                    add_thread(
+                        insts,
+                        &mut nlist,
+                        pc + 1,
+                        input,
+                        sp + 1,
+                        &mut slots,
+                        &mut stack,
+                    );
                 }
-                Inst::Match => {
-                    if sp == input.len() {
-                        matched_thread = Some(th);
-                        break 'outer;
+                Inst::Match if search.anchored != Anchored::Both || end_of_input => {
+                    if search.earliest {
+                        return Some(clist.slots(pc).to_vec());
+                    }
+                    let found = clist.slots(pc);
+                    match search.match_kind {
+                        MatchKind::LeftmostFirst => {
+                            // Remaining threads have lower priority, so they can't take over this match.
+                            // Threads already added to the next list have higher priority, and may find a longer one.
+                            matched = Some(found.to_vec());
+                            break;
+                        }
+                        MatchKind::LeftmostLongest => {
+                            // Threads are ordered by start position, and later positions make longer matches.
+                            let better = matched.as_ref().is_none_or(|m| {
+                                found[0] < m[0] || (found[0] == m[0] && found[1] > m[1])
+                            });
+                            if better {
+                                matched = Some(found.to_vec());
+                            }
+                        }
                     }
                 }
                 _ => {
-                    // Jmp, Split, Save handled in addthread, so that
+                    // Jmp, Split, Save handled in add_thread, so that
                     // machine execution matches what a backtracker would do.
                     // This is discussed (but not shown as code) in
                     // Regular Expression Matching: the Virtual Machine Approach.
@@ -185,9 +297,246 @@ pub fn run_vm<I>(insts: &[Inst<I>], input: &[I]) -> Option<Thread> {
             }
         }
 
-        clist = nlist;
+        std::mem::swap(&mut clist, &mut nlist);
+        nlist.set.clear();
         sp += 1;
     }
 
-    matched_thread
+    matched
+}
+
+/// Run a program of multiple patterns over `input` in a single pass, and returns saved positions of each pattern's match.
+///
+/// `pattern_starts[p]` is the first pc of the pattern `p`, and `pattern_of_pc` maps each pc to its pattern.
+/// Patterns don't share any pc, so their threads never take over each other,
+/// and each pattern reports its own leftmost-first match.
+/// If `search.earliest` is true, each pattern stops at its first thread reaching `Inst::Match`.
+/// `search.match_kind` is ignored.
+pub fn run_vm_set<I>(
+    insts: &[Inst<I>],
+    pattern_starts: &[PC],
+    pattern_of_pc: &[usize],
+    input: &[I],
+    start: SP,
+    search: Search,
+) -> Vec<Option<Slots>> {
+    let mut matched: Vec<Option<Slots>> = vec![None; pattern_starts.len()];
+    if start > input.len() {
+        return matched;
+    }
+
+    let slot_count = search.slot_count;
+    let prog_size = insts.len();
+    let mut clist = ThreadList::new(prog_size, slot_count);
+    let mut nlist = ThreadList::new(prog_size, slot_count);
+    let mut slots = vec![None; slot_count];
+    let mut stack = vec![];
+    // Patterns whose lower priority threads are cut by a match at the current position.
+    let mut cut = vec![false; pattern_starts.len()];
+    let mut remaining = pattern_starts.len();
+    let mut sp = start;
+
+    while sp <= input.len() && remaining > 0 {
+        let can_start = sp == start || search.anchored == Anchored::No;
+        if can_start {
+            for (pattern, &pattern_start) in pattern_starts.iter().enumerate() {
+                if matched[pattern].is_none() {
+                    slots.fill(None);
+                    add_thread(
+                        insts,
+                        &mut clist,
+                        pattern_start,
+                        input,
+                        sp,
+                        &mut slots,
+                        &mut stack,
+                    );
+                }
+            }
+        }
+        if clist.set.dense.is_empty() && (!can_start || matched.iter().all(Option::is_some)) {
+            break;
+        }
+
+        let end_of_input = sp == input.len();
+        cut.fill(false);
+        for &pc in clist.set.dense.iter() {
+            let pattern = pattern_of_pc[pc];
+            if cut[pattern] || (search.earliest && matched[pattern].is_some()) {
+                continue;
+            }
+            match &insts[pc] {
+                Inst::Check(predicate, _) if !end_of_input && predicate.test(input, sp) => {
+                    slots.copy_from_slice(clist.slots(pc));
+                    add_thread(
+                        insts,
+                        &mut nlist,
+                        pc + 1,
+                        input,
+                        sp + 1,
+                        &mut slots,
+                        &mut stack,
+                    );
+                }
+                Inst::Match if search.anchored != Anchored::Both || end_of_input => {
+                    if matched[pattern].is_none() && search.earliest {
+                        remaining -= 1;
+                    }
+                    matched[pattern] = Some(clist.slots(pc).to_vec());
+                    cut[pattern] = true;
+                }
+                _ => {}
+            }
+        }
+
+        std::mem::swap(&mut clist, &mut nlist);
+        nlist.set.clear();
+        sp += 1;
+    }
+
+    matched
+}
+
+/// Run the program anchored at `start`, and returns end positions of every match in ascending order.
+///
+/// If `anchored_end` is true, only the match ending at the end of input is reported.
+pub fn run_vm_ends<I>(insts: &[Inst<I>], input: &[I], start: SP, anchored_end: bool) -> Vec<SP> {
+    let mut ends = vec![];
+    if start > input.len() {
+        return ends;
+    }
+
+    let prog_size = insts.len();
+    let mut clist = ThreadList::new(prog_size, 0);
+    let mut nlist = ThreadList::new(prog_size, 0);
+    let mut stack = vec![];
+    let mut sp = start;
+    add_thread(insts, &mut clist, 0, input, sp, &mut [], &mut stack);
+
+    while !clist.set.dense.is_empty() {
+        let end_of_input = sp == input.len();
+        for &pc in clist.set.dense.iter() {
+            match &insts[pc] {
+                Inst::Check(predicate, _) if !end_of_input && predicate.test(input, sp) => {
+                    add_thread(
+                        insts,
+                        &mut nlist,
+                        pc + 1,
+                        input,
+                        sp + 1,
+                        &mut [],
+                        &mut stack,
+                    );
+                }
+                Inst::Match if !anchored_end || end_of_input => ends.push(sp),
+                _ => {}
+            }
+        }
+        if end_of_input {
+            break;
+        }
+
+        std::mem::swap(&mut clist, &mut nlist);
+        nlist.set.clear();
+        sp += 1;
+    }
+
+    ends
+}
+
+/// Run look-around sub-program anchored at `start`, and returns whether it matches.
+///
+/// Look-behind sub-program is compiled from the reversed regex and consumes input backward.
+/// Captures are not tracked, and the search stops as soon as any thread reaches `Inst::Match`.
+pub fn run_look<I>(insts: &[Inst<I>], input: &[I], start: SP, backward: bool) -> bool {
+    let mut sp = start;
+    let mut clist = vec![];
+    let mut seen_pc = vec![false; insts.len()];
+    if add_look_pc(insts, input, 0, sp, &mut seen_pc, &mut clist) {
+        return true;
+    }
+
+    while !clist.is_empty() {
+        if (backward && sp == 0) || (!backward && sp == input.len()) {
+            break;
+        }
+        let (index, next_sp) = if backward {
+            (sp - 1, sp - 1)
+        } else {
+            (sp, sp + 1)
+        };
+
+        let mut nlist = vec![];
+        let mut seen_pc = vec![false; insts.len()];
+        for pc in clist.into_iter() {
+            if let Inst::Check(predicate, _) = &insts[pc] {
+                if predicate.test(input, index)
+                    && add_look_pc(insts, input, pc + 1, next_sp, &mut seen_pc, &mut nlist)
+                {
+                    return true;
+                }
+            }
+        }
+
+        clist = nlist;
+        sp = next_sp;
+    }
+
+    false
+}
+
+// Follow empty transitions from `pc`, and push pcs waiting for input into `list`.
+// Returns true if `Inst::Match` is reachable without consuming input.
+fn add_look_pc<I>(
+    insts: &[Inst<I>],
+    input: &[I],
+    pc: PC,
+    sp: SP,
+    seen_pc: &mut [bool],
+    list: &mut Vec<PC>,
+) -> bool {
+    let mut stack = vec![pc];
+    while let Some(pc) = stack.pop() {
+        if seen_pc[pc] {
+            continue;
+        }
+        seen_pc[pc] = true;
+
+        match &insts[pc] {
+            Inst::Match => return true,
+            Inst::Check(_, _) => list.push(pc),
+            Inst::Begin => {
+                if sp == 0 {
+                    stack.push(pc + 1);
+                }
+            }
+            Inst::End => {
+                if sp == input.len() {
+                    stack.push(pc + 1);
+                }
+            }
+            Inst::Jmp(x) => stack.push(*x),
+            Inst::Split(x, y) => {
+                stack.push(*y);
+                stack.push(*x);
+            }
+            Inst::SaveOpen(_)
+            | Inst::SaveClose(_)
+            | Inst::SaveNamedOpen(_, _)
+            | Inst::SaveNamedClose(_, _) => stack.push(pc + 1),
+            Inst::LookAhead(look_insts, negate) => {
+                if run_look(look_insts, input, sp, false) != *negate {
+                    stack.push(pc + 1);
+                }
+            }
+            Inst::LookBehind(look_insts, negate) => {
+                if run_look(look_insts, input, sp, true) != *negate {
+                    stack.push(pc + 1);
+                }
+            }
+            Inst::BackRef(_, _) => unreachable!("back reference is not allowed in look-around"),
+        }
+    }
+
+    false
 }
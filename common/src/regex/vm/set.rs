@@ -0,0 +1,185 @@
+use crate::{CompileError, Match, Regex};
+
+use super::{
+    compiler::Pattern,
+    inst::{Inst, PC},
+    options::{Anchored, CompileOptions, MatchKind},
+    runner::{run_vm_set, Search, Slots},
+};
+
+/// Multiple regexes compiled into a single program, which are matched in a single pass over the input.
+///
+/// Each pattern ends with its own `Match` instruction, and reports its own leftmost-first match.
+/// The program always runs on the Pike VM, so back references are not supported.
+/// `CompileOptions::anchored` is respected, while the engine and the match kind are ignored.
+pub struct RegexSet<I> {
+    insts: Vec<Inst<I>>,
+    pattern_starts: Vec<PC>,
+    pattern_of_pc: Vec<usize>,
+    anchored: Anchored,
+}
+
+impl<I> RegexSet<I> {
+    /// Compile regexes with default options.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any regex is invalid, or the set is too large.
+    pub fn new(regs: impl IntoIterator<Item = Regex<I>>) -> Self {
+        match Self::try_new(regs, &CompileOptions::default()) {
+            Ok(set) => set,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Compile regexes with given options, returning an error if any regex is invalid or the set is too large.
+    pub fn try_new(
+        regs: impl IntoIterator<Item = Regex<I>>,
+        options: &CompileOptions,
+    ) -> Result<Self, CompileError> {
+        let patterns = regs
+            .into_iter()
+            .map(Pattern::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        let size = patterns
+            .iter()
+            .fold(0usize, |size, pattern| size.saturating_add(pattern.size));
+        if size > options.size_limit {
+            return Err(CompileError::SizeLimitExceeded {
+                size,
+                limit: options.size_limit,
+            });
+        }
+
+        let mut insts = vec![];
+        let mut pattern_starts = vec![];
+        let mut pattern_of_pc = vec![];
+        for (index, pattern) in patterns.into_iter().enumerate() {
+            let start_pc = insts.len();
+            let (pattern_insts, _) = pattern.compile(start_pc);
+            if pattern_insts
+                .iter()
+                .any(|inst| matches!(inst, Inst::BackRef(_, _)))
+            {
+                return Err(CompileError::BackRefNotSupported);
+            }
+            pattern_starts.push(start_pc);
+            pattern_of_pc.resize(start_pc + pattern_insts.len(), index);
+            insts.extend(pattern_insts);
+        }
+
+        Ok(Self {
+            insts,
+            pattern_starts,
+            pattern_of_pc,
+            anchored: options.anchored,
+        })
+    }
+
+    /// Returns the number of patterns in the set.
+    pub fn len(&self) -> usize {
+        self.pattern_starts.len()
+    }
+
+    /// Returns true if the set has no pattern.
+    pub fn is_empty(&self) -> bool {
+        self.pattern_starts.is_empty()
+    }
+
+    /// Returns true if and only if any pattern in the set matches the slice given.
+    pub fn is_match(&self, input: &[I]) -> bool {
+        self.matches(input).matched_any()
+    }
+
+    /// Returns which patterns in the set match the slice given.
+    pub fn matches(&self, input: &[I]) -> SetMatches {
+        let matched = self.run(input, 0, true);
+        SetMatches {
+            matched: matched.iter().map(Option::is_some).collect(),
+        }
+    }
+
+    /// Returns the leftmost-first match of each pattern in the set, indexed by the pattern index.
+    /// Patterns which don't match yield None.
+    pub fn find_all<'t>(&self, input: &'t [I]) -> Vec<Option<Match<'t, I>>> {
+        self.run(input, 2, false)
+            .into_iter()
+            .map(|slots| {
+                slots.map(|slots| match (slots[0], slots[1]) {
+                    (Some(start), Some(end)) => Match { input, start, end },
+                    _ => panic!("Unexpected missing 0th capture."),
+                })
+            })
+            .collect()
+    }
+
+    fn run(&self, input: &[I], slot_count: usize, earliest: bool) -> Vec<Option<Slots>> {
+        let search = Search {
+            slot_count,
+            earliest,
+            anchored: self.anchored,
+            match_kind: MatchKind::LeftmostFirst,
+        };
+        run_vm_set(
+            &self.insts,
+            &self.pattern_starts,
+            &self.pattern_of_pc,
+            input,
+            0,
+            search,
+        )
+    }
+}
+
+impl<I> Clone for RegexSet<I> {
+    fn clone(&self) -> Self {
+        Self {
+            insts: self.insts.clone(),
+            pattern_starts: self.pattern_starts.clone(),
+            pattern_of_pc: self.pattern_of_pc.clone(),
+            anchored: self.anchored,
+        }
+    }
+}
+
+impl<I> std::fmt::Debug for RegexSet<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RegexSet")
+            .field("insts", &self.insts)
+            .field("pattern_starts", &self.pattern_starts)
+            .field("anchored", &self.anchored)
+            .finish()
+    }
+}
+
+/// Set of patterns that matched, returned by `RegexSet::matches`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetMatches {
+    matched: Vec<bool>,
+}
+
+impl SetMatches {
+    /// Returns true if any pattern matched.
+    pub fn matched_any(&self) -> bool {
+        self.matched.iter().any(|matched| *matched)
+    }
+
+    /// Returns true if the pattern at `index` matched.
+    pub fn matched(&self, index: usize) -> bool {
+        self.matched.get(index).copied().unwrap_or(false)
+    }
+
+    /// Returns an iterator over the indices of patterns that matched, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.matched
+            .iter()
+            .enumerate()
+            .filter_map(|(index, matched)| matched.then_some(index))
+    }
+
+    /// Returns the number of patterns in the set.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.matched.len()
+    }
+}
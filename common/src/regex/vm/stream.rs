@@ -0,0 +1,422 @@
+use std::{borrow::Borrow, collections::VecDeque};
+
+use crate::{CompileError, CompiledRegex};
+
+use super::{
+    compiler::CompiledRegexInVm,
+    inst::{Inst, SP},
+    options::{Anchored, MatchKind},
+    runner::{add_thread_with_end, Frame, Slots, ThreadList},
+};
+
+/// A match reported by `StreamMatcher`. Offsets are positions in the whole stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchEvent {
+    start: usize,
+    end: usize,
+}
+
+impl MatchEvent {
+    /// Returns the starting offset of the match in the stream.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the ending offset of the match in the stream.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the range over the starting and ending offsets of the match in the stream.
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// Push-based matcher which consumes values one at a time,
+/// and reports the same non-overlapping matches as `find_iter` over the whole stream.
+///
+/// Threads of the Pike VM are kept between calls, so the whole stream is never buffered.
+/// Only values after the end of an undecided match are kept, because the search resumes
+/// at the end of the match once it is decided.
+///
+/// Look-around, back references and `MatchKind::LeftmostLongest` are not supported.
+///
+/// ```
+/// use vec_reg_common::{Regex, StreamMatcher};
+///
+/// let reg = Regex::repeat1(Regex::is(1), true).compile();
+/// let mut matcher = StreamMatcher::new(&reg).unwrap();
+/// assert_eq!(matcher.feed(&1), None);
+/// assert_eq!(matcher.feed(&1), None);
+/// assert_eq!(matcher.feed(&0).map(|m| m.range()), Some(0..2));
+/// assert_eq!(matcher.feed(&1), None);
+/// let rest: Vec<_> = matcher.finish().iter().map(|m| m.range()).collect();
+/// assert_eq!(rest, vec![3..4]);
+/// ```
+pub struct StreamMatcher<'r, I> {
+    regex: &'r CompiledRegexInVm<I>,
+    clist: ThreadList,
+    nlist: ThreadList,
+    slots: Vec<Option<SP>>,
+    stack: Vec<Frame>,
+    // Values from `buffer_start`, kept to resume the search and to test pair predicates.
+    buffer: VecDeque<I>,
+    buffer_start: usize,
+    // Number of values fed so far.
+    len: usize,
+    // Position of the VM, which is behind `len` while resuming the search over buffered values.
+    sp: SP,
+    // Position the current search starts.
+    search_start: usize,
+    // Match found by the current search, which may still be taken over by higher priority threads.
+    matched: Option<Slots>,
+    // End of the last reported match, to skip an empty match right after it.
+    last_match: Option<usize>,
+    // No more match can be found, because the search is anchored.
+    exhausted: bool,
+    events: VecDeque<MatchEvent>,
+}
+
+impl<'r, I: Clone> StreamMatcher<'r, I> {
+    /// Build a matcher at the start of a stream.
+    ///
+    /// Returns an error if the regex contains look-around or back references,
+    /// or if it is compiled with `MatchKind::LeftmostLongest`.
+    pub fn new(regex: &'r CompiledRegexInVm<I>) -> Result<Self, CompileError> {
+        let streamable = regex.match_kind() == MatchKind::LeftmostFirst
+            && regex.insts.iter().all(|inst| {
+                !matches!(
+                    inst,
+                    Inst::LookAhead(_, _) | Inst::LookBehind(_, _) | Inst::BackRef(_, _)
+                )
+            });
+        if !streamable {
+            return Err(CompileError::NotStreamable);
+        }
+
+        let prog_size = regex.insts.len();
+        Ok(Self {
+            regex,
+            clist: ThreadList::new(prog_size, 2),
+            nlist: ThreadList::new(prog_size, 2),
+            slots: vec![None; 2],
+            stack: vec![],
+            buffer: VecDeque::new(),
+            buffer_start: 0,
+            len: 0,
+            sp: 0,
+            search_start: 0,
+            matched: None,
+            last_match: None,
+            exhausted: false,
+            events: VecDeque::new(),
+        })
+    }
+
+    /// Feed the next value of the stream, and returns a match if it is decided.
+    ///
+    /// If more than one match is decided at once, the rest are returned by the following calls or by `finish`.
+    pub fn feed(&mut self, value: &I) -> Option<MatchEvent> {
+        self.buffer.push_back(value.clone());
+        self.len += 1;
+        self.run();
+        self.trim_buffer();
+
+        self.events.pop_front()
+    }
+
+    /// Tell the end of the stream, and returns the remaining matches.
+    pub fn finish(mut self) -> Vec<MatchEvent> {
+        loop {
+            self.run();
+            if self.exhausted || self.sp > self.len {
+                break;
+            }
+            self.end_of_stream();
+            match self.matched.take() {
+                Some(matched) => self.report(matched),
+                None => break,
+            }
+        }
+
+        self.events.into_iter().collect()
+    }
+
+    // Whether a match is found, even if it may still be taken over.
+    fn found(&self) -> bool {
+        self.matched.is_some() || !self.events.is_empty()
+    }
+
+    // Run the VM over buffered values until it waits for the next value.
+    fn run(&mut self) {
+        while !self.exhausted && self.sp <= self.len {
+            self.start_thread(None);
+            if self.regex.anchored != Anchored::Both {
+                self.take_match();
+            }
+            if self.clist.set.dense.is_empty() {
+                if let Some(matched) = self.matched.take() {
+                    self.report(matched);
+                    continue;
+                }
+                if self.regex.anchored != Anchored::No && self.sp >= self.search_start {
+                    self.exhausted = true;
+                    break;
+                }
+            }
+            if self.sp == self.len {
+                break;
+            }
+            self.step();
+        }
+    }
+
+    // Start a new search at the current position, until a match is found.
+    fn start_thread(&mut self, at_end: Option<bool>) {
+        let can_start = self.sp == self.search_start
+            || (self.sp > self.search_start && self.regex.anchored == Anchored::No);
+        if self.matched.is_none() && can_start {
+            self.slots.fill(None);
+            add_thread_with_end(
+                &self.regex.insts,
+                &mut self.clist,
+                0,
+                &[],
+                self.sp,
+                at_end,
+                &mut self.slots,
+                &mut self.stack,
+            );
+        }
+    }
+
+    // Take the highest priority thread reaching `Inst::Match`, and cut lower priority threads.
+    fn take_match(&mut self) {
+        let insts = &self.regex.insts;
+        let dense = &self.clist.set.dense;
+        if let Some(i) = dense
+            .iter()
+            .position(|pc| matches!(insts[*pc], Inst::Match))
+        {
+            self.matched = Some(self.clist.slots(dense[i]).to_vec());
+            self.clist.set.dense.truncate(i);
+        }
+    }
+
+    // Consume the value at the current position.
+    fn step(&mut self) {
+        let Self {
+            regex,
+            clist,
+            nlist,
+            slots,
+            stack,
+            buffer,
+            buffer_start,
+            sp,
+            ..
+        } = self;
+        let value = &buffer[*sp - *buffer_start];
+        let prev = sp.checked_sub(1).map(|prev| &buffer[prev - *buffer_start]);
+        for &pc in clist.set.dense.iter() {
+            if let Inst::Check(predicate, _) = &regex.insts[pc] {
+                if predicate.test_with(prev, value) {
+                    slots.copy_from_slice(clist.slots(pc));
+                    add_thread_with_end(
+                        &regex.insts,
+                        nlist,
+                        pc + 1,
+                        &[],
+                        *sp + 1,
+                        None,
+                        slots,
+                        stack,
+                    );
+                }
+            }
+        }
+
+        std::mem::swap(clist, nlist);
+        nlist.set.clear();
+        *sp += 1;
+    }
+
+    // Continue threads waiting for the end of input, and take the match at the end of the stream.
+    fn end_of_stream(&mut self) {
+        self.start_thread(Some(true));
+        let mut end_list = ThreadList::new(self.regex.insts.len(), 2);
+        for &pc in self.clist.set.dense.iter() {
+            let next_pc = match &self.regex.insts[pc] {
+                Inst::End => pc + 1,
+                Inst::Match => pc,
+                _ => continue,
+            };
+            self.slots.copy_from_slice(self.clist.slots(pc));
+            add_thread_with_end(
+                &self.regex.insts,
+                &mut end_list,
+                next_pc,
+                &[],
+                self.sp,
+                Some(true),
+                &mut self.slots,
+                &mut self.stack,
+            );
+        }
+        self.clist = end_list;
+        self.take_match();
+        self.clist.set.clear();
+    }
+
+    // Report the decided match, and resume the search at its end.
+    fn report(&mut self, matched: Slots) {
+        let (start, end) = match (matched[0], matched[1]) {
+            (Some(start), Some(end)) => (start, end),
+            _ => panic!("Unexpected missing 0th capture."),
+        };
+        let empty = start == end;
+        if !(empty && Some(end) == self.last_match) {
+            self.events.push_back(MatchEvent { start, end });
+            self.last_match = Some(end);
+        }
+        // Advance one value to make progress on empty match.
+        self.search_start = if empty { end + 1 } else { end };
+        self.sp = self.search_start;
+        self.clist.set.clear();
+    }
+
+    // Drop values which are never visited again, keeping one more value for pair predicates.
+    fn trim_buffer(&mut self) {
+        let keep = match &self.matched {
+            Some(matched) => matched[1].map_or(self.sp, |end| end.min(self.sp)),
+            None => self.sp,
+        };
+        while self.buffer_start + 1 < keep && !self.buffer.is_empty() {
+            self.buffer.pop_front();
+            self.buffer_start += 1;
+        }
+    }
+}
+
+/// Iterator over non-overlapping matches in values of an iterator.
+///
+/// This is created by `CompiledRegexInVm::find_iter_positions`.
+pub struct IterMatches<'r, I, It> {
+    values: It,
+    matcher: Option<StreamMatcher<'r, I>>,
+    rest: std::vec::IntoIter<MatchEvent>,
+}
+
+impl<I, It> Iterator for IterMatches<'_, I, It>
+where
+    I: Clone,
+    It: Iterator,
+    It::Item: Borrow<I>,
+{
+    type Item = MatchEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.rest.next() {
+            return Some(event);
+        }
+        let matcher = self.matcher.as_mut()?;
+        for value in self.values.by_ref() {
+            if let Some(event) = matcher.feed(value.borrow()) {
+                return Some(event);
+            }
+        }
+        self.rest = self.matcher.take()?.finish().into_iter();
+        self.rest.next()
+    }
+}
+
+impl<I: Clone> CompiledRegexInVm<I> {
+    /// Returns true if values of the iterator match the regex.
+    ///
+    /// Values are given to the Pike VM one at a time, and the iteration stops at the first match.
+    /// If the regex contains look-around or back references, or is compiled with `MatchKind::LeftmostLongest`,
+    /// values are collected first.
+    ///
+    /// ```
+    /// use vec_reg_common::Regex;
+    ///
+    /// let reg = Regex::seq(&[3, 4]).compile();
+    /// assert!(reg.is_match_iter((1..).take(10)));
+    /// assert!(!reg.is_match_iter((1..10).filter(|x| x % 2 == 0)));
+    /// ```
+    pub fn is_match_iter<It>(&self, values: It) -> bool
+    where
+        It: IntoIterator,
+        It::Item: Borrow<I>,
+    {
+        let mut matcher = match StreamMatcher::new(self) {
+            Ok(matcher) => matcher,
+            Err(_) => return self.is_match(&collect_values(values)),
+        };
+        for value in values {
+            if matcher.feed(value.borrow()).is_some() || matcher.found() {
+                return true;
+            }
+        }
+
+        !matcher.finish().is_empty()
+    }
+
+    /// Returns an iterator over non-overlapping matches in values of the iterator.
+    ///
+    /// Values are given to the Pike VM one at a time, and only offsets of the matches are reported.
+    /// If the regex contains look-around or back references, or is compiled with `MatchKind::LeftmostLongest`,
+    /// values are collected first.
+    ///
+    /// ```
+    /// use std::collections::VecDeque;
+    /// use vec_reg_common::Regex;
+    ///
+    /// let reg = Regex::repeat1(Regex::is(1), true).compile();
+    /// let values = VecDeque::from(vec![1, 1, 0, 1]);
+    /// let ranges: Vec<_> = reg.find_iter_positions(&values).map(|m| m.range()).collect();
+    /// assert_eq!(ranges, vec![0..2, 3..4]);
+    /// ```
+    pub fn find_iter_positions<It>(&self, values: It) -> IterMatches<'_, I, It::IntoIter>
+    where
+        It: IntoIterator,
+        It::Item: Borrow<I>,
+    {
+        match StreamMatcher::new(self) {
+            Ok(matcher) => IterMatches {
+                values: values.into_iter(),
+                matcher: Some(matcher),
+                rest: vec![].into_iter(),
+            },
+            Err(_) => {
+                let mut values = values.into_iter();
+                let input = collect_values(values.by_ref());
+                let events: Vec<_> = self
+                    .find_iter(&input)
+                    .map(|m| MatchEvent {
+                        start: m.start(),
+                        end: m.end(),
+                    })
+                    .collect();
+                IterMatches {
+                    values,
+                    matcher: None,
+                    rest: events.into_iter(),
+                }
+            }
+        }
+    }
+}
+
+fn collect_values<I, It>(values: It) -> Vec<I>
+where
+    I: Clone,
+    It: IntoIterator,
+    It::Item: Borrow<I>,
+{
+    values
+        .into_iter()
+        .map(|value| value.borrow().clone())
+        .collect()
+}
@@ -0,0 +1,194 @@
+use std::ops::Range;
+
+use super::{
+    inst::{Inst, Predicate, PC, SP},
+    render::predicate_syntax,
+};
+
+/// Record of a search run by `CompiledRegexInVm::trace`.
+///
+/// It has a step for each position visited by the Pike VM, with the threads alive
+/// at the position and the predicates tested there. `Display` prints a human-readable report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchTrace {
+    pub(super) steps: Vec<TraceStep>,
+    pub(super) matched: Option<Range<usize>>,
+}
+
+/// Threads alive at a position of the input, and predicates tested there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    position: usize,
+    threads: Vec<TraceThread>,
+    pub(super) tests: Vec<PredicateTest>,
+}
+
+/// A thread waiting for the value at a position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceThread {
+    pc: PC,
+    start: usize,
+    location: String,
+}
+
+/// A predicate tested against the value at a position, and its result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PredicateTest {
+    pc: PC,
+    location: String,
+    result: bool,
+}
+
+impl MatchTrace {
+    pub(super) fn new() -> Self {
+        Self {
+            steps: vec![],
+            matched: None,
+        }
+    }
+
+    /// Returns the steps of the search, in order of position.
+    pub fn steps(&self) -> &[TraceStep] {
+        &self.steps
+    }
+
+    /// Returns the range of the match found by the search.
+    pub fn matched(&self) -> Option<Range<usize>> {
+        self.matched.clone()
+    }
+
+    /// Returns the furthest position reached by a thread which consumed at least one value,
+    /// as a range from the position the thread started at.
+    ///
+    /// When the regex doesn't match, this is where the most promising attempt got stuck.
+    pub fn furthest(&self) -> Option<Range<usize>> {
+        let mut furthest: Option<Range<usize>> = None;
+        for step in self.steps.iter() {
+            for thread in step.threads.iter() {
+                if thread.start < step.position
+                    && furthest.as_ref().is_none_or(|f| step.position > f.end)
+                {
+                    furthest = Some(thread.start..step.position);
+                }
+            }
+        }
+
+        furthest
+    }
+}
+
+impl std::fmt::Display for MatchTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in self.steps.iter() {
+            writeln!(f, "position {}:", step.position)?;
+            for thread in step.threads.iter() {
+                write!(
+                    f,
+                    "  pc {} {} (from {})",
+                    thread.pc, thread.location, thread.start
+                )?;
+                match step.tests.iter().find(|test| test.pc == thread.pc) {
+                    Some(test) if test.result => writeln!(f, ": ok")?,
+                    Some(_) => writeln!(f, ": failed")?,
+                    None => writeln!(f)?,
+                }
+            }
+        }
+        match self.furthest() {
+            Some(furthest) => writeln!(
+                f,
+                "furthest: position {} from {}",
+                furthest.end, furthest.start
+            )?,
+            None => writeln!(f, "furthest: no value consumed")?,
+        }
+        match &self.matched {
+            Some(matched) => write!(f, "match: {}..{}", matched.start, matched.end),
+            None => write!(f, "no match"),
+        }
+    }
+}
+
+impl TraceStep {
+    pub(super) fn new(position: SP, threads: Vec<TraceThread>) -> Self {
+        Self {
+            position,
+            threads,
+            tests: vec![],
+        }
+    }
+
+    /// Returns the position of the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the threads alive at the position, in priority order.
+    pub fn threads(&self) -> &[TraceThread] {
+        &self.threads
+    }
+
+    /// Returns the predicates tested against the value at the position, in order.
+    pub fn tests(&self) -> &[PredicateTest] {
+        &self.tests
+    }
+}
+
+impl TraceThread {
+    pub(super) fn new<I>(inst: &Inst<I>, pc: PC, start: Option<SP>) -> Self {
+        Self {
+            pc,
+            start: start.unwrap_or_default(),
+            location: location(inst),
+        }
+    }
+
+    /// Returns the pc of the instruction the thread waits at.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// Returns the position the thread started at.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns where the thread is in the pattern, like `[is_fizz]` or `Match`.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+}
+
+impl PredicateTest {
+    pub(super) fn new<I>(inst: &Inst<I>, pc: PC, result: bool) -> Self {
+        Self {
+            pc,
+            location: location(inst),
+            result,
+        }
+    }
+
+    /// Returns the pc of the tested `Check` instruction.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// Returns the tested predicate in the pattern, like `[is_fizz]`.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Returns whether the value satisfied the predicate.
+    pub fn result(&self) -> bool {
+        self.result
+    }
+}
+
+// Describe the instruction a thread waits at, in the syntax of the pattern.
+fn location<I>(inst: &Inst<I>) -> String {
+    match inst {
+        Inst::Check(Predicate::Value(_), label) => predicate_syntax(label.as_deref(), "<fn>"),
+        Inst::Check(Predicate::Pair(_), label) => predicate_syntax(label.as_deref(), "<pair fn>"),
+        inst => format!("{:?}", inst),
+    }
+}
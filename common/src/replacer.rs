@@ -0,0 +1,117 @@
+use crate::Captures;
+
+/// Replacer describes types that can be used to replace matches in a slice.
+///
+/// It is implemented for any `FnMut(&Captures<I>) -> impl IntoIterator<Item = I>` closure and for [`Template`].
+pub trait Replacer<I> {
+    /// Appends the replacement for the given captures to `dst`.
+    fn replace_append(&mut self, caps: &Captures<'_, I>, dst: &mut Vec<I>);
+}
+
+impl<I, F, T> Replacer<I> for F
+where
+    F: FnMut(&Captures<'_, I>) -> T,
+    T: IntoIterator<Item = I>,
+{
+    fn replace_append(&mut self, caps: &Captures<'_, I>, dst: &mut Vec<I>) {
+        dst.extend((*self)(caps));
+    }
+}
+
+#[derive(Debug, Clone)]
+enum TemplatePart<I> {
+    Value(I),
+    Group(usize),
+    NamedGroup(String),
+}
+
+/// Template is a replacement built from literal values and references to capture groups.
+///
+/// A referenced group that did not participate in the match expands to nothing.
+///
+/// ```rust
+/// use vec_reg_common::{CompiledRegex, Regex, Template};
+///
+/// let reg = Regex::concat(
+///     Regex::group(Regex::is(1)),
+///     Regex::named_group("second", Regex::is(2)),
+/// )
+/// .compile();
+/// let template = Template::new().named_group("second").value(0).group(1);
+/// assert_eq!(reg.replace_all(&[1, 2, 3, 1, 2], &template), vec![2, 0, 1, 3, 2, 0, 1]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Template<I> {
+    parts: Vec<TemplatePart<I>>,
+}
+
+impl<I> Template<I> {
+    /// Build empty template, which replaces matches with nothing.
+    pub fn new() -> Self {
+        Self { parts: vec![] }
+    }
+
+    /// Append a literal value.
+    pub fn value(mut self, value: I) -> Self {
+        self.parts.push(TemplatePart::Value(value));
+        self
+    }
+
+    /// Append literal values.
+    pub fn values(mut self, values: impl IntoIterator<Item = I>) -> Self {
+        self.parts
+            .extend(values.into_iter().map(TemplatePart::Value));
+        self
+    }
+
+    /// Append the sub-slice captured by the group at the given index.
+    pub fn group(mut self, index: usize) -> Self {
+        self.parts.push(TemplatePart::Group(index));
+        self
+    }
+
+    /// Append the sub-slice captured by the group with the given name.
+    pub fn named_group(mut self, name: &str) -> Self {
+        self.parts.push(TemplatePart::NamedGroup(name.to_owned()));
+        self
+    }
+
+    fn expand(&self, caps: &Captures<'_, I>, dst: &mut Vec<I>)
+    where
+        I: Clone,
+    {
+        for part in self.parts.iter() {
+            match part {
+                TemplatePart::Value(value) => dst.push(value.clone()),
+                TemplatePart::Group(index) => {
+                    if let Some(m) = caps.get(*index) {
+                        dst.extend_from_slice(m.values());
+                    }
+                }
+                TemplatePart::NamedGroup(name) => {
+                    if let Some(m) = caps.name(name) {
+                        dst.extend_from_slice(m.values());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<I> Default for Template<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Clone> Replacer<I> for Template<I> {
+    fn replace_append(&mut self, caps: &Captures<'_, I>, dst: &mut Vec<I>) {
+        self.expand(caps, dst);
+    }
+}
+
+impl<I: Clone> Replacer<I> for &Template<I> {
+    fn replace_append(&mut self, caps: &Captures<'_, I>, dst: &mut Vec<I>) {
+        self.expand(caps, dst);
+    }
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::{compile_with, ENGINES};
+use vec_reg_common::{Anchored, CompileOptions, CompiledRegex, CompiledRegexInVm, Engine, Regex};
+
+fn compile(reg: Regex<i32>, engine: Engine, anchored: Anchored) -> CompiledRegexInVm<i32> {
+    compile_with(&reg, engine, CompileOptions::new().anchored(anchored))
+}
+
+#[test]
+fn full_match_longer_than_leftmost_first() {
+    for engine in ENGINES {
+        let reg = compile(
+            Regex::or(Regex::is(1), Regex::seq(&[1, 2])),
+            engine,
+            Anchored::No,
+        );
+        assert_eq!(reg.find(&[1, 2]).unwrap().range(), 0..1);
+        assert!(reg.is_full_match(&[1, 2]), "{:?}", engine);
+        assert!(reg.is_full_match(&[1]), "{:?}", engine);
+        assert!(!reg.is_full_match(&[0, 1]), "{:?}", engine);
+    }
+}
+
+#[test]
+fn full_match_non_greedy() {
+    let reg = Regex::repeat1(Regex::any(), false).compile();
+    assert!(reg.is_full_match(&[1, 2, 3]));
+    assert!(!reg.is_full_match(&[]));
+}
+
+#[test]
+fn anchored_start() {
+    for engine in ENGINES {
+        let reg = compile(Regex::is(1), engine, Anchored::Start);
+        assert_eq!(reg.anchored(), Anchored::Start);
+        assert!(reg.is_match(&[1, 0]), "{:?}", engine);
+        assert!(!reg.is_match(&[0, 1]), "{:?}", engine);
+        assert_eq!(reg.find_at(&[0, 1], 1).unwrap().range(), 1..2);
+        assert!(reg.find_at(&[0, 0, 1], 1).is_none(), "{:?}", engine);
+    }
+}
+
+#[test]
+fn anchored_start_find_iter() {
+    for engine in ENGINES {
+        let reg = compile(Regex::is(1), engine, Anchored::Start);
+        let ranges: Vec<_> = reg.find_iter(&[1, 1, 0, 1]).map(|m| m.range()).collect();
+        assert_eq!(ranges, vec![0..1, 1..2], "{:?}", engine);
+    }
+}
+
+#[test]
+fn anchored_both() {
+    for engine in ENGINES {
+        let reg = compile(
+            Regex::group(Regex::repeat1(Regex::is(1), false)),
+            engine,
+            Anchored::Both,
+        );
+        let captures = reg.captures(&[1, 1, 1]).unwrap();
+        assert_eq!(captures.get(1).unwrap().range(), 0..3, "{:?}", engine);
+        assert!(reg.find(&[1, 1, 0]).is_none(), "{:?}", engine);
+        assert!(reg.find(&[0, 1, 1]).is_none(), "{:?}", engine);
+        assert_eq!(reg.find_at(&[0, 1, 1], 1).unwrap().range(), 1..3);
+    }
+}
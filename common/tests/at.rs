@@ -0,0 +1,63 @@
+use vec_reg_common::{CompiledRegex, Regex};
+
+#[test]
+fn is_match_at() {
+    let reg = Regex::is(1).compile();
+    assert!(reg.is_match_at(&[1, 2, 3], 0));
+    assert!(!reg.is_match_at(&[1, 2, 3], 1));
+    assert!(reg.is_match_at(&[1, 2, 1], 1));
+}
+
+#[test]
+fn find_at_returns_absolute_offsets() {
+    let reg = Regex::repeat1(Regex::is(1), true).compile();
+    let found = reg.find_at(&[1, 1, 2, 1, 1, 1], 2);
+    assert!(found.is_some());
+    assert_eq!(found.as_ref().unwrap().range(), 3..6);
+    assert_eq!(found.as_ref().unwrap().values(), &[1, 1, 1]);
+}
+
+#[test]
+fn find_at_inside_match() {
+    let reg = Regex::repeat1(Regex::is(1), true).compile();
+    let found = reg.find_at(&[1, 1, 1], 1);
+    assert_eq!(found.unwrap().range(), 1..3);
+}
+
+#[test]
+fn find_at_end_of_input() {
+    let reg = Regex::repeat0(Regex::is(1), true).compile();
+    assert_eq!(reg.find_at(&[1, 1], 2).unwrap().range(), 2..2);
+    assert!(reg.find_at(&[1, 1], 3).is_none());
+}
+
+#[test]
+fn begin_is_start_of_whole_input() {
+    let reg = Regex::concat(Regex::begin(), Regex::is(1)).compile();
+    assert!(reg.is_match_at(&[1, 1], 0));
+    assert!(!reg.is_match_at(&[1, 1], 1));
+    assert!(reg.find_at(&[2, 1], 1).is_none());
+}
+
+#[test]
+fn end_is_end_of_whole_input() {
+    let reg = Regex::concat(Regex::is(1), Regex::end()).compile();
+    assert_eq!(reg.find_at(&[1, 2, 1], 1).unwrap().range(), 2..3);
+}
+
+#[test]
+fn captures_at() {
+    let reg = Regex::concat(
+        Regex::named_group("even", Regex::satisfy(|x: &i32| x % 2 == 0)),
+        Regex::group(Regex::satisfy(|x: &i32| x % 2 == 1)),
+    )
+    .compile();
+    let captures = reg.captures_at(&[2, 3, 4, 5], 1);
+    assert!(captures.is_some());
+    assert_eq!(captures.as_ref().unwrap().get(0).unwrap().range(), 2..4);
+    assert_eq!(
+        captures.as_ref().unwrap().name("even").unwrap().range(),
+        2..3
+    );
+    assert_eq!(captures.as_ref().unwrap().get(2).unwrap().range(), 3..4);
+}
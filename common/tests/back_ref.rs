@@ -0,0 +1,186 @@
+use vec_reg_common::{
+    CompileError, CompileOptions, CompiledRegex, MatchError, MatchKind, Regex,
+    DEFAULT_BACKTRACK_LIMIT,
+};
+
+fn request_response() -> Regex<i32> {
+    // (id+) 0 \1
+    Regex::concat(
+        Regex::group(Regex::repeat1(Regex::satisfy(|x: &i32| *x > 0), true)),
+        Regex::concat(Regex::is(0), Regex::back_ref(1)),
+    )
+}
+
+#[test]
+fn back_ref() {
+    let reg = request_response().compile();
+    assert!(reg.is_full_match(&[1, 2, 0, 1, 2]));
+    assert!(!reg.is_full_match(&[1, 2, 0, 2, 1]));
+    assert!(!reg.is_full_match(&[1, 2, 0, 1]));
+}
+
+#[test]
+fn back_ref_find() {
+    let reg = request_response().compile();
+    let found = reg.find(&[-1, 3, 4, 0, 4, 5]);
+    assert!(found.is_some());
+    // Group shrinks to [4] so that back reference matches.
+    assert_eq!(found.unwrap().range(), 2..5);
+}
+
+#[test]
+fn back_ref_captures() {
+    let reg = request_response().compile();
+    let captures = reg.captures(&[7, 1, 2, 0, 1, 2, 9]).unwrap();
+    assert_eq!(captures.get(0).unwrap().range(), 1..6);
+    assert_eq!(captures.get(1).unwrap().values(), &[1, 2]);
+}
+
+#[test]
+fn named_back_ref() {
+    let reg = Regex::concat(
+        Regex::named_group("id", Regex::repeat1(Regex::satisfy(|x: &i32| *x > 0), true)),
+        Regex::concat(Regex::is(0), Regex::named_back_ref("id")),
+    )
+    .compile();
+    assert!(reg.is_full_match(&[3, 0, 3]));
+    assert!(!reg.is_full_match(&[3, 0, 4]));
+}
+
+#[test]
+fn back_ref_to_unmatched_group() {
+    let reg = Regex::concat(
+        Regex::or(Regex::group(Regex::is(1)), Regex::is(2)),
+        Regex::back_ref(1),
+    )
+    .compile();
+    assert!(reg.is_full_match(&[1, 1]));
+    assert!(!reg.is_match(&[2]));
+}
+
+#[test]
+fn back_ref_in_repetition() {
+    // ((\d)\2)+ matches pairs of same values.
+    let reg = Regex::repeat1(
+        Regex::group(Regex::concat(
+            Regex::group(Regex::any()),
+            Regex::back_ref(2),
+        )),
+        true,
+    )
+    .compile();
+    assert!(reg.is_full_match(&[1, 1, 2, 2, 3, 3]));
+    assert!(!reg.is_full_match(&[1, 1, 2, 3]));
+}
+
+#[test]
+fn back_ref_empty_group() {
+    let reg = Regex::repeat0(
+        Regex::concat(
+            Regex::group(Regex::repeat0(Regex::is(1), true)),
+            Regex::back_ref(1),
+        ),
+        true,
+    )
+    .compile();
+    assert!(reg.is_full_match(&[]));
+    assert!(reg.is_full_match(&[1, 1]));
+    assert!(!reg.is_full_match(&[1, 1, 1]));
+}
+
+#[test]
+fn back_ref_compile_errors() {
+    assert_eq!(
+        Regex::<i32>::back_ref(1).try_compile().err(),
+        Some(CompileError::UnknownGroup(1))
+    );
+    assert_eq!(
+        Regex::<i32>::named_back_ref("id").try_compile().err(),
+        Some(CompileError::UnknownGroupName("id".to_owned()))
+    );
+    assert_eq!(
+        Regex::concat(
+            Regex::group(Regex::is(1)),
+            Regex::look_ahead(Regex::back_ref(1))
+        )
+        .try_compile()
+        .err(),
+        Some(CompileError::BackRefInLookAround)
+    );
+}
+
+#[test]
+fn repeated_group_index() {
+    let reg = Regex::concat(
+        Regex::repeat_n(Regex::group(Regex::any()), 2),
+        Regex::group(Regex::is(9)),
+    )
+    .compile();
+    assert_eq!(reg.captures_len(), 3);
+    let captures = reg.captures(&[1, 2, 9]).unwrap();
+    assert_eq!(captures.get(1).unwrap().values(), &[2]);
+    assert_eq!(captures.get(2).unwrap().values(), &[9]);
+}
+
+#[test]
+fn back_ref_after_group_repeated_zero_times() {
+    let reg = Regex::concat(
+        Regex::concat(
+            Regex::repeat_n(Regex::group(Regex::is(1)), 0),
+            Regex::group(Regex::is(2)),
+        ),
+        Regex::back_ref(2),
+    )
+    .compile();
+    assert_eq!(reg.find(&[2, 2]).unwrap().range(), 0..2);
+    assert!(reg.find(&[2, 3]).is_none());
+}
+
+#[test]
+fn backtrack_limit() {
+    // (.)(?:.+)*[9]\1 takes exponential time on a run of digits without 9.
+    let is_digit = |x: &i32| (0..10).contains(x);
+    let reg = Regex::concat(
+        Regex::concat(
+            Regex::group(Regex::satisfy(is_digit)),
+            Regex::repeat0(Regex::repeat1(Regex::satisfy(is_digit), true), true),
+        ),
+        Regex::concat(Regex::is(9), Regex::back_ref(1)),
+    );
+    let input = [0; 40];
+
+    let compiled = reg.clone().compile();
+    assert_eq!(
+        compiled.try_find(&input).unwrap_err(),
+        MatchError::BacktrackLimitExceeded {
+            limit: DEFAULT_BACKTRACK_LIMIT
+        }
+    );
+
+    let limited = reg
+        .clone()
+        .try_compile_with(&CompileOptions::new().backtrack_limit(1000))
+        .unwrap();
+    assert!(limited.try_captures(&input).is_err());
+    assert!(limited.find(&input).is_none());
+    assert!(!limited.is_match(&input));
+
+    let longest = reg
+        .try_compile_with(
+            &CompileOptions::new()
+                .match_kind(MatchKind::LeftmostLongest)
+                .backtrack_limit(1000),
+        )
+        .unwrap();
+    assert_eq!(
+        longest.try_find(&input).unwrap_err(),
+        MatchError::BacktrackLimitExceeded { limit: 1000 }
+    );
+
+    // Short inputs are still searched to the end.
+    assert_eq!(
+        compiled.try_find(&[0, 0, 9, 0]).unwrap().unwrap().range(),
+        0..4
+    );
+    assert!(compiled.try_find(&[0, 0, 9, 1]).unwrap().is_none());
+}
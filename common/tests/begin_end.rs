@@ -1,3 +1,6 @@
+mod common;
+
+use common::{compile, ENGINES};
 use vec_reg_common::{CompiledRegex, Regex};
 
 #[test]
@@ -8,8 +11,10 @@ fn begin_end() {
             Regex::concat(Regex::begin(), Regex::end()),
         ),
         Regex::group(Regex::repeat0(Regex::any(), false)),
-    )
-    .compile();
-    let captures = reg.captures(&[1]);
-    assert!(captures.is_none());
+    );
+    for engine in ENGINES {
+        let reg = compile(&reg, engine);
+        let captures = reg.captures(&[1]);
+        assert!(captures.is_none(), "{:?}", engine);
+    }
 }
@@ -1,10 +1,16 @@
+mod common;
+
+use common::{compile, ENGINES};
 use vec_reg_common::{CompiledRegex, Regex};
 
 #[test]
 fn without_capture() {
-    let reg = Regex::is(1).compile();
-    assert!(reg.captures(&[1]).is_some());
-    assert!(reg.captures(&[1]).unwrap().get(1).is_none());
+    let reg = Regex::is(1);
+    for engine in ENGINES {
+        let reg = compile(&reg, engine);
+        assert!(reg.captures(&[1]).is_some(), "{:?}", engine);
+        assert!(reg.captures(&[1]).unwrap().get(1).is_none(), "{:?}", engine);
+    }
 }
 
 #[test]
@@ -14,18 +20,20 @@ fn with_capture() {
     let reg = Regex::concat(
         Regex::group(Regex::repeat1(Regex::satisfy(is_even), true)),
         Regex::group(Regex::repeat1(Regex::satisfy(is_odd), true)),
-    )
-    .compile();
-    let captures = reg.captures(&[2, 4, 6, 3, 5, 7]);
-    assert!(captures.is_some());
+    );
+    for engine in ENGINES {
+        let reg = compile(&reg, engine);
+        let captures = reg.captures(&[2, 4, 6, 3, 5, 7]);
+        assert!(captures.is_some(), "{:?}", engine);
 
-    let capture_1 = &captures.as_ref().unwrap().get(1).unwrap();
-    assert_eq!(capture_1.range(), 0..3);
-    assert_eq!(capture_1.values(), &[2, 4, 6]);
+        let capture_1 = &captures.as_ref().unwrap().get(1).unwrap();
+        assert_eq!(capture_1.range(), 0..3, "{:?}", engine);
+        assert_eq!(capture_1.values(), &[2, 4, 6], "{:?}", engine);
 
-    let capture_2 = &captures.as_ref().unwrap().get(2).unwrap();
-    assert_eq!(capture_2.range(), 3..6);
-    assert_eq!(capture_2.values(), &[3, 5, 7]);
+        let capture_2 = &captures.as_ref().unwrap().get(2).unwrap();
+        assert_eq!(capture_2.range(), 3..6, "{:?}", engine);
+        assert_eq!(capture_2.values(), &[3, 5, 7], "{:?}", engine);
+    }
 }
 
 #[test]
@@ -35,14 +43,16 @@ fn non_capture_group() {
     let reg = Regex::concat(
         Regex::non_capturing_group(Regex::repeat1(Regex::satisfy(is_even), true)),
         Regex::group(Regex::repeat1(Regex::satisfy(is_odd), true)),
-    )
-    .compile();
-    let captures = reg.captures(&[2, 4, 6, 3, 5, 7]);
-    assert!(captures.is_some());
+    );
+    for engine in ENGINES {
+        let reg = compile(&reg, engine);
+        let captures = reg.captures(&[2, 4, 6, 3, 5, 7]);
+        assert!(captures.is_some(), "{:?}", engine);
 
-    let capture_1 = &captures.as_ref().unwrap().get(1).unwrap();
-    assert_eq!(capture_1.range(), 3..6);
-    assert_eq!(capture_1.values(), &[3, 5, 7]);
+        let capture_1 = &captures.as_ref().unwrap().get(1).unwrap();
+        assert_eq!(capture_1.range(), 3..6, "{:?}", engine);
+        assert_eq!(capture_1.values(), &[3, 5, 7], "{:?}", engine);
+    }
 }
 
 #[test]
@@ -52,20 +62,71 @@ fn named_capture_group() {
     let reg = Regex::concat(
         Regex::named_group("is_even", Regex::repeat1(Regex::satisfy(is_even), true)),
         Regex::group(Regex::repeat1(Regex::satisfy(is_odd), true)),
-    )
-    .compile();
-    let captures = reg.captures(&[2, 4, 6, 3, 5, 7]);
-    assert!(captures.is_some());
+    );
+    for engine in ENGINES {
+        let reg = compile(&reg, engine);
+        let captures = reg.captures(&[2, 4, 6, 3, 5, 7]);
+        assert!(captures.is_some(), "{:?}", engine);
+
+        let capture_1 = &captures.as_ref().unwrap().get(1).unwrap();
+        assert_eq!(capture_1.range(), 0..3, "{:?}", engine);
+        assert_eq!(capture_1.values(), &[2, 4, 6], "{:?}", engine);
 
-    let capture_1 = &captures.as_ref().unwrap().get(1).unwrap();
-    assert_eq!(capture_1.range(), 0..3);
-    assert_eq!(capture_1.values(), &[2, 4, 6]);
+        let capture_is_even = &captures.as_ref().unwrap().name("is_even").unwrap();
+        assert_eq!(capture_is_even.range(), 0..3, "{:?}", engine);
+        assert_eq!(capture_is_even.values(), &[2, 4, 6], "{:?}", engine);
+
+        let capture_2 = &captures.as_ref().unwrap().get(2).unwrap();
+        assert_eq!(capture_2.range(), 3..6, "{:?}", engine);
+        assert_eq!(capture_2.values(), &[3, 5, 7], "{:?}", engine);
+    }
+}
 
-    let capture_is_even = &captures.as_ref().unwrap().name("is_even").unwrap();
-    assert_eq!(capture_is_even.range(), 0..3);
-    assert_eq!(capture_is_even.values(), &[2, 4, 6]);
+#[test]
+fn unmatched_capture_group() {
+    let reg = Regex::concat(
+        Regex::or(
+            Regex::named_group("one", Regex::is(1)),
+            Regex::group(Regex::is(2)),
+        ),
+        Regex::group(Regex::is(3)),
+    );
+    for engine in ENGINES {
+        let reg = compile(&reg, engine);
+        let captures = reg.captures(&[2, 3]).unwrap();
+        assert_eq!(captures.len(), 4, "{:?}", engine);
+        assert!(captures.get(1).is_none(), "{:?}", engine);
+        assert!(captures.name("one").is_none(), "{:?}", engine);
+        assert_eq!(captures.get(2).unwrap().range(), 0..1, "{:?}", engine);
+        assert_eq!(captures.get(3).unwrap().range(), 1..2, "{:?}", engine);
+    }
+}
 
-    let capture_2 = &captures.as_ref().unwrap().get(2).unwrap();
-    assert_eq!(capture_2.range(), 3..6);
-    assert_eq!(capture_2.values(), &[3, 5, 7]);
+#[test]
+fn group_repeated_zero_times() {
+    let reg = Regex::concat(
+        Regex::repeat_n(Regex::group(Regex::is(1)), 0),
+        Regex::named_group("b", Regex::is(2)),
+    );
+    for engine in ENGINES {
+        let reg = compile(&reg, engine);
+        assert_eq!(reg.captures_len(), 3, "{:?}", engine);
+        let captures = reg.captures(&[2]).unwrap();
+        assert!(captures.get(1).is_none(), "{:?}", engine);
+        assert_eq!(captures.name("b").unwrap().range(), 0..1, "{:?}", engine);
+    }
+}
+
+#[test]
+fn trailing_group_repeated_zero_times() {
+    let reg = Regex::concat(
+        Regex::is(2),
+        Regex::repeat_min_max(Regex::named_group("a", Regex::is(1)), 0, 0, true),
+    );
+    for engine in ENGINES {
+        let reg = compile(&reg, engine);
+        assert_eq!(reg.captures_len(), 2, "{:?}", engine);
+        let captures = reg.captures(&[2]).unwrap();
+        assert!(captures.name("a").is_none(), "{:?}", engine);
+    }
 }
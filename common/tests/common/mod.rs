@@ -0,0 +1,20 @@
+// Helpers shared by the tests running every engine.
+#![allow(dead_code)]
+
+use vec_reg_common::{CompileOptions, CompiledRegexInVm, Engine, Regex};
+
+pub const ENGINES: [Engine; 2] = [Engine::PikeVm, Engine::Backtrack];
+
+pub fn compile<I: Clone + 'static>(reg: &Regex<I>, engine: Engine) -> CompiledRegexInVm<I> {
+    compile_with(reg, engine, CompileOptions::new())
+}
+
+pub fn compile_with<I: Clone + 'static>(
+    reg: &Regex<I>,
+    engine: Engine,
+    options: CompileOptions,
+) -> CompiledRegexInVm<I> {
+    reg.clone()
+        .try_compile_with(&options.engine(engine))
+        .unwrap()
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use vec_reg_common::{CompiledRegex, CompiledRegexInVm, Regex};
+
+struct Rules {
+    rules: HashMap<&'static str, CompiledRegexInVm<i32>>,
+}
+
+#[test]
+fn store_in_collections() {
+    let mut rules = Rules {
+        rules: HashMap::new(),
+    };
+    rules
+        .rules
+        .insert("ones", Regex::repeat1(Regex::is(1), true).compile());
+    rules.rules.insert("two", Regex::is(2).compile());
+
+    assert!(rules.rules["ones"].is_match(&[0, 1]));
+    assert!(!rules.rules["two"].is_match(&[0, 1]));
+
+    let compiled: Vec<CompiledRegexInVm<i32>> =
+        vec![Regex::is(1).compile(), Regex::is(2).compile()];
+    assert_eq!(compiled.iter().filter(|r| r.is_match(&[2])).count(), 1);
+}
+
+#[test]
+fn clone() {
+    let reg = Regex::is(1).compile();
+    let cloned = reg.clone();
+    assert!(cloned.is_match(&[1]));
+    assert!(reg.is_match(&[1]));
+}
+
+#[test]
+fn debug() {
+    let reg = Regex::named_group("one", Regex::is(1)).compile();
+    let debug = format!("{:?}", reg);
+    assert!(debug.starts_with("CompiledRegexInVm"));
+    assert!(debug.contains("\"one\""));
+}
+
+#[test]
+fn captures_len() {
+    assert_eq!(Regex::is(1).compile().captures_len(), 1);
+    let reg = Regex::concat(
+        Regex::group(Regex::is(1)),
+        Regex::named_group("two", Regex::group(Regex::is(2))),
+    )
+    .compile();
+    assert_eq!(reg.captures_len(), 4);
+}
+
+#[test]
+fn capture_names() {
+    let reg = Regex::concat(
+        Regex::group(Regex::is(1)),
+        Regex::named_group("two", Regex::group(Regex::is(2))),
+    )
+    .compile();
+    let names: Vec<_> = reg.capture_names().collect();
+    assert_eq!(names, vec![None, None, Some("two"), None]);
+}
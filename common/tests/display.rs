@@ -0,0 +1,104 @@
+use vec_reg_common::{PredicateRegistry, Regex};
+
+fn a() -> Regex<i32> {
+    Regex::is(1)
+}
+
+#[test]
+fn display_alternation_in_concat() {
+    let reg = Regex::concat(Regex::or(a(), a()), a());
+    assert_eq!(reg.to_string(), "(?:[<fn>]|[<fn>])[<fn>]");
+    let reg = Regex::or(Regex::concat(a(), a()), a());
+    assert_eq!(reg.to_string(), "[<fn>][<fn>]|[<fn>]");
+    let reg = Regex::or(a(), Regex::or(a(), a()));
+    assert_eq!(reg.to_string(), "[<fn>]|[<fn>]|[<fn>]");
+}
+
+#[test]
+fn display_repetition() {
+    let reg = Regex::repeat1(Regex::concat(a(), a()), true);
+    assert_eq!(reg.to_string(), "(?:[<fn>][<fn>])+");
+    let reg = Regex::repeat0(Regex::repeat1(a(), false), true);
+    assert_eq!(reg.to_string(), "(?:[<fn>]+?)*");
+    let reg = Regex::zero_or_one(Regex::group(Regex::or(a(), a())), false);
+    assert_eq!(reg.to_string(), "([<fn>]|[<fn>])??");
+    let reg = Regex::repeat_min_max(Regex::<i32>::end(), 1, 2, true);
+    assert_eq!(reg.to_string(), "(?:$){1,2}");
+    let reg = Regex::repeat_n_or_more(a(), 2, false);
+    assert_eq!(reg.to_string(), "[<fn>]{2,}?");
+    let reg = Regex::repeat_n(a(), 3);
+    assert_eq!(reg.to_string(), "[<fn>]{3}");
+}
+
+#[test]
+fn display_groups() {
+    let reg = Regex::named_group("x", Regex::or(a(), a()));
+    assert_eq!(reg.to_string(), "(?P<\"x\">[<fn>]|[<fn>])");
+    let reg = Regex::concat(
+        Regex::<i32>::begin(),
+        Regex::concat(Regex::look_ahead(a()), Regex::named_back_ref("x")),
+    );
+    assert_eq!(reg.to_string(), "^(?=[<fn>])(?P=\"x\")");
+    let reg = Regex::concat(Regex::not_satisfy(|x: &i32| *x > 0), Regex::back_ref(1));
+    assert_eq!(reg.to_string(), "[^<fn>](?P=1)");
+}
+
+#[test]
+fn display_labels() {
+    let is_fizz = |x: &i32| x % 3 == 0;
+    let reg = Regex::concat(
+        Regex::repeat1(Regex::satisfy_named("is_fizz", is_fizz), true),
+        Regex::or(
+            Regex::not_satisfy_named("is_fizz", is_fizz),
+            Regex::concat(
+                Regex::any(),
+                Regex::not_satisfy_pair_named("increasing", |a: &i32, b: &i32| b > a),
+            ),
+        ),
+    );
+    assert_eq!(reg.to_string(), "[is_fizz]+(?:[^is_fizz]|.[^increasing])");
+    assert_eq!(
+        format!("{:?}", Regex::satisfy_named("is_fizz", is_fizz)),
+        "Satisfy(\"is_fizz\")"
+    );
+    let compiled = Regex::concat(
+        Regex::satisfy_named("is_fizz", is_fizz),
+        Regex::satisfy(is_fizz),
+    )
+    .compile();
+    let debug = format!("{:?}", compiled);
+    assert!(debug.contains("Check(\"is_fizz\")"), "{}", debug);
+    assert!(debug.contains("Check(\"<fn>\")"), "{}", debug);
+}
+
+#[test]
+fn display_round_trip() {
+    let mut registry = PredicateRegistry::new();
+    registry.insert("a", |x: &i32| *x == 1);
+    registry.insert("b", |x: &i32| *x == 2);
+    registry.insert_pair("inc", |a: &i32, b: &i32| b > a);
+    registry.set_eq(|a: &i32, b: &i32| a == b);
+    let patterns = [
+        "[a]|[b][^a]",
+        "(?:[a]|[b])+?[inc]{2,}",
+        "^(?P<\"x\">[a]|.)(?P=\"x\")(?P=1)$",
+        "(?:[a][b])*(?=[b]|[^inc])(?<![a]){1,2}?",
+        "(?:^)?(?:$){3}",
+    ];
+    for pattern in patterns {
+        let reg = Regex::parse(pattern, &registry).unwrap();
+        assert_eq!(reg.to_string(), pattern);
+    }
+}
+
+#[test]
+fn labels_in_program() {
+    let reg = Regex::concat(
+        Regex::satisfy_named("is_fizz", |x: &i32| x % 3 == 0),
+        Regex::not_satisfy_named("is_buzz", |x: &i32| x % 5 == 0),
+    )
+    .compile();
+    let dump = format!("{:?}", reg);
+    assert!(dump.contains("Check(\"is_fizz\")"), "{}", dump);
+    assert!(dump.contains("Check(\"^is_buzz\")"), "{}", dump);
+}
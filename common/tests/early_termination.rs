@@ -0,0 +1,81 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use vec_reg_common::{CompileOptions, CompiledRegex, Engine, Regex};
+
+// Regex matching a value greater than `threshold`, counting how many values are tested.
+fn counting_regex(threshold: i32, count: Arc<AtomicUsize>) -> Regex<i32> {
+    Regex::satisfy(move |x: &i32| {
+        count.fetch_add(1, Ordering::SeqCst);
+        *x > threshold
+    })
+}
+
+#[test]
+fn is_match_stops_at_first_match() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let reg = counting_regex(0, count.clone()).compile();
+    let mut input = vec![0; 1000];
+    input[2] = 1;
+    assert!(reg.is_match(&input));
+    assert!(count.load(Ordering::SeqCst) <= 3);
+}
+
+#[test]
+fn find_stops_when_match_is_decided() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let reg = Regex::repeat1(counting_regex(0, count.clone()), true).compile();
+    let mut input = vec![0; 1000];
+    input[2] = 1;
+    input[3] = 1;
+    assert_eq!(reg.find(&input).unwrap().range(), 2..4);
+    assert!(count.load(Ordering::SeqCst) <= 5);
+}
+
+#[test]
+fn is_match_with_backtrack() {
+    let count = Arc::new(AtomicUsize::new(0));
+    let reg = counting_regex(0, count.clone())
+        .try_compile_with(&CompileOptions::new().engine(Engine::Backtrack))
+        .unwrap();
+    let mut input = vec![0; 1000];
+    input[2] = 1;
+    assert!(reg.is_match(&input));
+    assert!(count.load(Ordering::SeqCst) <= 3);
+}
+
+#[test]
+fn find_prefers_leftmost_first() {
+    let reg = Regex::or(Regex::is(1), Regex::seq(&[1, 2])).compile();
+    assert_eq!(reg.find(&[0, 1, 2]).unwrap().range(), 1..2);
+    let reg = Regex::or(Regex::seq(&[1, 2]), Regex::is(1)).compile();
+    assert_eq!(reg.find(&[0, 1, 2]).unwrap().range(), 1..3);
+}
+
+#[test]
+fn find_does_not_scan_after_match() {
+    for engine in [Engine::PikeVm, Engine::Backtrack] {
+        let count = Arc::new(AtomicUsize::new(0));
+        let reg = counting_regex(0, count.clone())
+            .try_compile_with(&CompileOptions::new().engine(engine))
+            .unwrap();
+        let mut input = vec![1; 1000];
+        input[0] = 0;
+        assert_eq!(reg.find(&input).unwrap().range(), 1..2);
+        assert!(count.load(Ordering::SeqCst) <= 3, "{:?}", engine);
+    }
+}
+
+#[test]
+fn leftmost_match_wins_over_later_start() {
+    for engine in [Engine::PikeVm, Engine::Backtrack] {
+        // A match starting at 0 ends after a match starting at 1 would.
+        let reg = Regex::or(Regex::seq(&[1, 2, 3]), Regex::is(2))
+            .try_compile_with(&CompileOptions::new().engine(engine))
+            .unwrap();
+        assert_eq!(reg.find(&[1, 2, 3]).unwrap().range(), 0..3, "{:?}", engine);
+        assert_eq!(reg.find(&[1, 2, 4]).unwrap().range(), 1..2, "{:?}", engine);
+    }
+}
@@ -1,3 +1,6 @@
+mod common;
+
+use common::{compile, ENGINES};
 use vec_reg_common::{CompiledRegex, Regex};
 
 #[test]
@@ -5,11 +8,18 @@ fn end_at_end() {
     let reg = Regex::concat(
         Regex::group(Regex::repeat0(Regex::is(1), true)),
         Regex::end(),
-    )
-    .compile();
-    let captures = reg.captures(&[1, 1]);
-    assert!(captures.is_some());
-    assert_eq!(captures.as_ref().unwrap().get(1).unwrap().range(), 0..2);
+    );
+    for engine in ENGINES {
+        let reg = compile(&reg, engine);
+        let captures = reg.captures(&[1, 1]);
+        assert!(captures.is_some(), "{:?}", engine);
+        assert_eq!(
+            captures.as_ref().unwrap().get(1).unwrap().range(),
+            0..2,
+            "{:?}",
+            engine
+        );
+    }
 }
 
 #[test]
@@ -17,9 +27,16 @@ fn end_at_internal() {
     let reg = Regex::concat(
         Regex::end(),
         Regex::group(Regex::repeat0(Regex::is(1), true)),
-    )
-    .compile();
-    let captures = reg.captures(&[1, 1]);
-    assert!(captures.is_some());
-    assert_eq!(captures.as_ref().unwrap().get(1).unwrap().range(), 2..2);
+    );
+    for engine in ENGINES {
+        let reg = compile(&reg, engine);
+        let captures = reg.captures(&[1, 1]);
+        assert!(captures.is_some(), "{:?}", engine);
+        assert_eq!(
+            captures.as_ref().unwrap().get(1).unwrap().range(),
+            2..2,
+            "{:?}",
+            engine
+        );
+    }
 }
@@ -0,0 +1,189 @@
+mod common;
+
+use common::{compile, ENGINES};
+use vec_reg_common::{
+    CompileError, CompileOptions, CompiledRegex, CompiledRegexInVm, Engine, Regex,
+};
+
+// Input, and expected (start, end) of each capture group if matched.
+// Capture groups which didn't participate in the match are None.
+type Case<'a> = (&'a [i32], Option<&'a [Option<(usize, usize)>]>);
+
+fn captures(reg: &CompiledRegexInVm<i32>, input: &[i32]) -> Option<Vec<Option<(usize, usize)>>> {
+    reg.captures(input).map(|caps| {
+        (0..caps.len())
+            .map(|i| caps.get(i).map(|m| (m.start(), m.end())))
+            .collect()
+    })
+}
+
+// Assert both engines report the expected captures for each input.
+fn assert_captures(reg: Regex<i32>, cases: &[Case]) {
+    for engine in ENGINES {
+        let compiled = compile(&reg, engine);
+        assert_eq!(compiled.engine(), engine);
+        for (input, expected) in cases {
+            let expected = expected.map(|groups| groups.to_vec());
+            assert_eq!(
+                captures(&compiled, input),
+                expected,
+                "{:?} on {:?}",
+                engine,
+                input
+            );
+        }
+    }
+}
+
+#[test]
+fn default_engine() {
+    assert_eq!(Regex::is(1).compile().engine(), Engine::PikeVm);
+    let back_ref = Regex::concat(Regex::group(Regex::is(1)), Regex::back_ref(1));
+    assert_eq!(back_ref.compile().engine(), Engine::Backtrack);
+}
+
+#[test]
+fn back_ref_requires_backtrack() {
+    let back_ref = Regex::concat(Regex::group(Regex::is(1)), Regex::back_ref(1));
+    let reg = back_ref.try_compile_with(&CompileOptions::new().engine(Engine::PikeVm));
+    assert_eq!(reg.err(), Some(CompileError::BackRefNotSupported));
+}
+
+#[test]
+fn greedy_and_non_greedy() {
+    assert_captures(
+        Regex::group(Regex::repeat1(Regex::is(1), true)),
+        &[
+            (&[0, 1, 1, 2], Some(&[Some((1, 3)), Some((1, 3))])),
+            (&[2], None),
+        ],
+    );
+    assert_captures(
+        Regex::group(Regex::repeat1(Regex::is(1), false)),
+        &[(&[0, 1, 1, 2], Some(&[Some((1, 2)), Some((1, 2))]))],
+    );
+    assert_captures(
+        Regex::concat(
+            Regex::group(Regex::repeat0(Regex::any(), false)),
+            Regex::is(2),
+        ),
+        &[(&[1, 2, 1, 2], Some(&[Some((0, 2)), Some((0, 1))]))],
+    );
+}
+
+#[test]
+fn alternation_priority() {
+    assert_captures(
+        Regex::or(
+            Regex::group(Regex::is(1)),
+            Regex::group(Regex::seq(&[1, 2])),
+        ),
+        &[(&[1, 2], Some(&[Some((0, 1)), Some((0, 1)), None]))],
+    );
+}
+
+#[test]
+fn begin_end() {
+    let reg = Regex::concat(
+        Regex::begin(),
+        Regex::concat(Regex::repeat1(Regex::is(1), true), Regex::end()),
+    );
+    assert_captures(
+        reg,
+        &[
+            (&[1, 1], Some(&[Some((0, 2))])),
+            (&[0, 1], None),
+            (&[1, 0], None),
+        ],
+    );
+}
+
+#[test]
+fn repetition_of_empty_match() {
+    assert_captures(
+        Regex::concat(
+            Regex::repeat0(Regex::repeat0(Regex::is(1), true), true),
+            Regex::is(2),
+        ),
+        &[(&[1, 1, 2], Some(&[Some((0, 3))])), (&[3], None)],
+    );
+}
+
+#[test]
+fn counted_repetition() {
+    assert_captures(
+        Regex::group(Regex::repeat_min_max(Regex::is(1), 2, 3, true)),
+        &[
+            (&[1, 1, 1, 1], Some(&[Some((0, 3)), Some((0, 3))])),
+            (&[1, 0, 1], None),
+        ],
+    );
+}
+
+#[test]
+fn look_around() {
+    assert_captures(
+        Regex::concat(
+            Regex::look_behind(Regex::is(0)),
+            Regex::concat(Regex::any(), Regex::negative_look_ahead(Regex::is(0))),
+        ),
+        &[(&[0, 1, 0, 2, 3], Some(&[Some((3, 4))])), (&[1, 2], None)],
+    );
+}
+
+#[test]
+fn satisfy_pair() {
+    assert_captures(
+        Regex::repeat1(
+            Regex::satisfy_pair(|prev: &i32, cur: &i32| cur > prev),
+            true,
+        ),
+        &[(&[3, 1, 2, 3, 3], Some(&[Some((2, 4))]))],
+    );
+}
+
+#[test]
+fn find_iter() {
+    let reg = Regex::repeat0(Regex::is(1), true);
+    for engine in ENGINES {
+        let compiled = compile(&reg, engine);
+        let ranges: Vec<_> = compiled
+            .find_iter(&[1, 2, 1, 1])
+            .map(|m| m.range())
+            .collect();
+        assert_eq!(ranges, vec![0..1, 2..4], "{:?}", engine);
+    }
+}
+
+#[test]
+fn find_at() {
+    let reg = Regex::concat(Regex::begin(), Regex::is(1));
+    for engine in ENGINES {
+        let compiled = compile(&reg, engine);
+        assert!(compiled.find_at(&[1, 1], 1).is_none(), "{:?}", engine);
+        assert!(compiled.is_match_at(&[1, 1], 0), "{:?}", engine);
+    }
+}
+
+#[test]
+fn long_input() {
+    // Longer than the visited set of the backtracker can cover, so it falls back to the Pike VM.
+    let input: Vec<i32> = (0..100_000).map(|x| x % 7).collect();
+    let reg = Regex::concat(
+        Regex::is(3),
+        Regex::repeat1(Regex::satisfy(|x: &i32| *x > 3), true),
+    );
+    for engine in ENGINES {
+        let compiled = compile(&reg, engine);
+        assert_eq!(compiled.engine(), engine);
+        assert_eq!(compiled.find_iter(&input).count(), 14_286, "{:?}", engine);
+        let last = compiled.find_at(&input, 99_990).unwrap();
+        assert_eq!(last.range(), 99_991..99_995, "{:?}", engine);
+        assert_eq!(
+            compiled.find_all_spans(&input[..14]).len(),
+            6,
+            "{:?}",
+            engine
+        );
+    }
+}
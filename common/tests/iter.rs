@@ -0,0 +1,77 @@
+use vec_reg_common::{CompiledRegex, Regex};
+
+#[test]
+fn find_iter_non_overlapping() {
+    let reg = Regex::repeat1(Regex::is(1), true).compile();
+    let ranges: Vec<_> = reg
+        .find_iter(&[1, 1, 2, 1, 3, 1, 1, 1])
+        .map(|m| m.range())
+        .collect();
+    assert_eq!(ranges, vec![0..2, 3..4, 5..8]);
+}
+
+#[test]
+fn find_iter_no_match() {
+    let reg = Regex::is(1).compile();
+    assert_eq!(reg.find_iter(&[2, 3, 4]).count(), 0);
+}
+
+#[test]
+fn find_iter_empty_input() {
+    let reg = Regex::repeat0(Regex::is(1), true).compile();
+    let ranges: Vec<_> = reg.find_iter(&[]).map(|m| m.range()).collect();
+    assert_eq!(ranges, vec![0..0]);
+}
+
+#[test]
+fn find_iter_empty_matches() {
+    let reg = Regex::repeat0(Regex::is(1), true).compile();
+    let ranges: Vec<_> = reg.find_iter(&[2, 1, 1, 2]).map(|m| m.range()).collect();
+    // Empty match right after a non-empty match is skipped.
+    assert_eq!(ranges, vec![0..0, 1..3, 4..4]);
+}
+
+#[test]
+fn find_iter_begin_only_matches_at_start() {
+    let reg = Regex::concat(Regex::begin(), Regex::is(1)).compile();
+    let ranges: Vec<_> = reg.find_iter(&[1, 1, 1]).map(|m| m.range()).collect();
+    assert_eq!(ranges, vec![0..1]);
+}
+
+#[test]
+fn captures_iter() {
+    let reg = Regex::concat(
+        Regex::named_group("even", Regex::satisfy(|x: &i32| x % 2 == 0)),
+        Regex::group(Regex::satisfy(|x: &i32| x % 2 == 1)),
+    )
+    .compile();
+    let input = [2, 3, 5, 4, 7, 6];
+    let captures: Vec<_> = reg.captures_iter(&input).collect();
+    assert_eq!(captures.len(), 2);
+
+    assert_eq!(captures[0].get(0).unwrap().range(), 0..2);
+    assert_eq!(captures[0].name("even").unwrap().values(), &[2]);
+    assert_eq!(captures[0].get(2).unwrap().values(), &[3]);
+
+    assert_eq!(captures[1].get(0).unwrap().range(), 3..5);
+    assert_eq!(captures[1].name("even").unwrap().values(), &[4]);
+    assert_eq!(captures[1].get(2).unwrap().values(), &[7]);
+}
+
+#[test]
+fn captures_iter_empty_matches() {
+    let reg = Regex::group(Regex::repeat0(Regex::is(1), true)).compile();
+    let ranges: Vec<_> = reg
+        .captures_iter(&[2, 1, 1, 2])
+        .map(|c| c.get(1).unwrap().range())
+        .collect();
+    assert_eq!(ranges, vec![0..0, 1..3, 4..4]);
+}
+
+#[test]
+fn find_through_trait_object() {
+    let reg: Box<dyn CompiledRegex<i32>> = Box::new(Regex::is(1).compile());
+    assert!(reg.is_match(&[2, 1]));
+    assert_eq!(reg.find(&[2, 1]).unwrap().range(), 1..2);
+    assert_eq!(reg.find_at(&[1, 2, 1], 1).unwrap().range(), 2..3);
+}
@@ -0,0 +1,82 @@
+use std::collections::{LinkedList, VecDeque};
+
+use vec_reg_common::{CompileOptions, CompiledRegex, MatchKind, Regex};
+
+#[test]
+fn is_match_iter() {
+    let reg = Regex::seq(&[3, 4]).compile();
+    assert!(reg.is_match_iter((1..).take(10)));
+    assert!(reg.is_match_iter(vec![3, 4]));
+    assert!(!reg.is_match_iter((1..10).filter(|x| x % 2 == 0)));
+    assert!(!reg.is_match_iter(Vec::<i32>::new()));
+}
+
+#[test]
+fn is_match_iter_stops_at_first_match() {
+    let reg = Regex::repeat1(Regex::is(1), true).compile();
+    // The iterator is infinite, so it must stop once a match is found.
+    assert!(reg.is_match_iter(std::iter::repeat(1)));
+}
+
+#[test]
+fn is_match_iter_end_of_input() {
+    let reg = Regex::concat(Regex::is(2), Regex::end()).compile();
+    assert!(reg.is_match_iter(vec![1, 2]));
+    assert!(!reg.is_match_iter(vec![2, 1]));
+}
+
+#[test]
+fn find_iter_positions_same_as_find_iter() {
+    let reg = Regex::or(Regex::seq(&[1, 2, 3]), Regex::repeat0(Regex::is(1), true)).compile();
+    let input = vec![1, 2, 1, 1, 2, 3, 0, 1];
+    let expected: Vec<_> = reg.find_iter(&input).map(|m| m.range()).collect();
+
+    let deque: VecDeque<_> = input.iter().copied().collect();
+    let ranges: Vec<_> = reg.find_iter_positions(&deque).map(|m| m.range()).collect();
+    assert_eq!(ranges, expected);
+
+    let list: LinkedList<_> = input.iter().copied().collect();
+    let ranges: Vec<_> = reg.find_iter_positions(list).map(|m| m.range()).collect();
+    assert_eq!(ranges, expected);
+}
+
+#[test]
+fn find_iter_positions_is_lazy() {
+    let reg = Regex::seq(&[0, 1]).compile();
+    let ranges: Vec<_> = reg
+        .find_iter_positions((0..).map(|x| x % 3))
+        .take(3)
+        .map(|m| m.range())
+        .collect();
+    assert_eq!(ranges, vec![0..2, 3..5, 6..8]);
+}
+
+#[test]
+fn find_iter_positions_with_back_ref() {
+    let reg = Regex::concat(Regex::group(Regex::any()), Regex::back_ref(1)).compile();
+    let input = [1, 1, 2, 3, 3];
+    let ranges: Vec<_> = reg
+        .find_iter_positions(input.iter().map(|x| x * 10))
+        .map(|m| m.range())
+        .collect();
+    assert_eq!(ranges, vec![0..2, 3..5]);
+    assert!(reg.is_match_iter(input.iter()));
+}
+
+#[test]
+fn find_iter_positions_leftmost_longest() {
+    let reg = Regex::or(Regex::is(1), Regex::seq(&[1, 2]))
+        .try_compile_with(&CompileOptions::new().match_kind(MatchKind::LeftmostLongest))
+        .unwrap();
+    let input = [1, 2, 1, 2];
+    let expected: Vec<_> = reg.find_iter(&input).map(|m| m.range()).collect();
+    assert_eq!(expected, vec![0..2, 2..4]);
+
+    let ranges: Vec<_> = reg
+        .find_iter_positions(input.iter())
+        .map(|m| m.range())
+        .collect();
+    assert_eq!(ranges, expected);
+    assert!(reg.is_match_iter(input.iter()));
+    assert!(!reg.is_match_iter([2, 3].iter()));
+}
@@ -0,0 +1,99 @@
+use vec_reg_common::{CompiledRegex, Regex};
+
+fn spike() -> Regex<i32> {
+    Regex::satisfy(|x: &i32| *x >= 10)
+}
+
+fn drop() -> Regex<i32> {
+    Regex::satisfy(|x: &i32| *x < 0)
+}
+
+#[test]
+fn look_ahead() {
+    let reg = Regex::concat(spike(), Regex::look_ahead(drop())).compile();
+    let found = reg.find(&[1, 10, 2, 12, -1]);
+    assert!(found.is_some());
+    // Look ahead does not consume the drop.
+    assert_eq!(found.unwrap().range(), 3..4);
+    assert!(!reg.is_match(&[1, 10, 2, 12]));
+}
+
+#[test]
+fn negative_look_ahead() {
+    let reg = Regex::concat(spike(), Regex::negative_look_ahead(drop())).compile();
+    assert_eq!(reg.find(&[10, -1, 12, 3]).unwrap().range(), 2..3);
+    // Negative look ahead succeeds at the end of input.
+    assert_eq!(reg.find(&[10, -1, 12]).unwrap().range(), 2..3);
+    assert!(!reg.is_match(&[10, -1]));
+}
+
+#[test]
+fn look_ahead_multiple_values() {
+    let reg = Regex::concat(spike(), Regex::look_ahead(Regex::concat(drop(), drop()))).compile();
+    assert_eq!(reg.find(&[10, -1, 3, 11, -1, -2]).unwrap().range(), 3..4);
+}
+
+#[test]
+fn look_behind() {
+    let reg = Regex::concat(Regex::look_behind(spike()), drop()).compile();
+    assert_eq!(reg.find(&[-1, 3, -2, 10, -3]).unwrap().range(), 4..5);
+    assert!(!reg.is_match(&[-1, 3, -2]));
+}
+
+#[test]
+fn negative_look_behind() {
+    let reg = Regex::concat(Regex::negative_look_behind(spike()), drop()).compile();
+    assert_eq!(reg.find(&[10, -1, 3, -2]).unwrap().range(), 3..4);
+    // Negative look behind succeeds at the beginning of input.
+    assert_eq!(reg.find(&[-1]).unwrap().range(), 0..1);
+    assert!(!reg.is_match(&[10, -1]));
+}
+
+#[test]
+fn look_behind_sequence_order() {
+    // (?<=[1][2])[3] only matches 3 preceded by 1, 2 in this order.
+    let reg = Regex::concat(Regex::look_behind(Regex::seq(&[1, 2])), Regex::is(3)).compile();
+    assert!(reg.is_match(&[1, 2, 3]));
+    assert!(!reg.is_match(&[2, 1, 3]));
+}
+
+#[test]
+fn look_behind_with_begin() {
+    let reg = Regex::concat(
+        Regex::look_behind(Regex::concat(Regex::begin(), Regex::is(1))),
+        Regex::is(2),
+    )
+    .compile();
+    assert!(reg.is_match(&[1, 2]));
+    assert!(!reg.is_match(&[1, 1, 2]));
+}
+
+#[test]
+fn look_behind_with_start_offset() {
+    let reg = Regex::concat(Regex::look_behind(Regex::is(1)), Regex::is(2)).compile();
+    // Look behind sees values before the start offset.
+    assert_eq!(reg.find_at(&[1, 2], 1).unwrap().range(), 1..2);
+}
+
+#[test]
+fn look_around_does_not_capture() {
+    let reg = Regex::concat(
+        Regex::look_ahead(Regex::group(Regex::is(1))),
+        Regex::group(Regex::is(1)),
+    )
+    .compile();
+    let captures = reg.captures(&[1]).unwrap();
+    assert_eq!(captures.len(), 2);
+    assert_eq!(captures.get(1).unwrap().range(), 0..1);
+}
+
+#[test]
+fn nested_look_around() {
+    // A value followed by a drop that is not preceded by a spike.
+    let reg = Regex::concat(
+        Regex::any(),
+        Regex::look_ahead(Regex::concat(Regex::negative_look_behind(spike()), drop())),
+    )
+    .compile();
+    assert_eq!(reg.find(&[10, -1, 2, -1]).unwrap().range(), 2..3);
+}
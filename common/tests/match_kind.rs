@@ -0,0 +1,112 @@
+mod common;
+
+use common::{compile_with, ENGINES};
+use vec_reg_common::{CompileOptions, CompiledRegex, CompiledRegexInVm, Engine, MatchKind, Regex};
+
+fn compile_longest(reg: Regex<char>, engine: Engine) -> CompiledRegexInVm<char> {
+    compile_with(
+        &reg,
+        engine,
+        CompileOptions::new().match_kind(MatchKind::LeftmostLongest),
+    )
+}
+
+#[test]
+fn default_match_kind() {
+    let reg = Regex::is('a').compile();
+    assert_eq!(reg.match_kind(), MatchKind::LeftmostFirst);
+}
+
+#[test]
+fn longest_alternation() {
+    for engine in ENGINES {
+        let reg = compile_longest(Regex::or(Regex::is('a'), Regex::seq(&['a', 'b'])), engine);
+        assert_eq!(
+            reg.find(&['x', 'a', 'b']).unwrap().range(),
+            1..3,
+            "{:?}",
+            engine
+        );
+        assert_eq!(reg.find(&['a', 'c']).unwrap().range(), 0..1, "{:?}", engine);
+    }
+}
+
+#[test]
+fn longest_ignores_non_greedy() {
+    for engine in ENGINES {
+        let reg = compile_longest(Regex::repeat1(Regex::is('a'), false), engine);
+        assert_eq!(
+            reg.find(&['a', 'a', 'a', 'b']).unwrap().range(),
+            0..3,
+            "{:?}",
+            engine
+        );
+    }
+}
+
+#[test]
+fn leftmost_before_longest() {
+    for engine in ENGINES {
+        // Match at 0 is shorter than the match at 1, but leftmost one wins.
+        let reg = compile_longest(
+            Regex::or(Regex::is('a'), Regex::seq(&['b', 'c', 'd'])),
+            engine,
+        );
+        assert_eq!(
+            reg.find(&['a', 'b', 'c', 'd']).unwrap().range(),
+            0..1,
+            "{:?}",
+            engine
+        );
+    }
+}
+
+#[test]
+fn longest_with_later_start_match_found_first() {
+    for engine in ENGINES {
+        // A match starting at 1 ends before the match starting at 0.
+        let reg = compile_longest(
+            Regex::or(Regex::seq(&['a', 'b', 'c']), Regex::is('b')),
+            engine,
+        );
+        assert_eq!(
+            reg.find(&['a', 'b', 'c']).unwrap().range(),
+            0..3,
+            "{:?}",
+            engine
+        );
+        assert_eq!(
+            reg.find(&['a', 'b', 'd']).unwrap().range(),
+            1..2,
+            "{:?}",
+            engine
+        );
+    }
+}
+
+#[test]
+fn longest_captures() {
+    for engine in ENGINES {
+        let reg = compile_longest(
+            Regex::concat(
+                Regex::group(Regex::repeat0(Regex::is('a'), false)),
+                Regex::group(Regex::repeat0(Regex::any(), false)),
+            ),
+            engine,
+        );
+        let captures = reg.captures(&['a', 'b']).unwrap();
+        assert_eq!(captures.get(0).unwrap().range(), 0..2, "{:?}", engine);
+    }
+}
+
+#[test]
+fn longest_find_iter() {
+    for engine in ENGINES {
+        let reg = compile_longest(Regex::repeat1(Regex::is('a'), false), engine);
+        let ranges: Vec<_> = reg
+            .find_iter(&['a', 'a', 'b', 'a'])
+            .map(|m| m.range())
+            .collect();
+        assert_eq!(ranges, vec![0..2, 3..4], "{:?}", engine);
+    }
+}
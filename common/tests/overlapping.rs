@@ -0,0 +1,74 @@
+mod common;
+
+use common::{compile, ENGINES};
+use vec_reg_common::{CompiledRegex, Regex};
+
+#[test]
+fn find_overlapping_iter() {
+    // Motif 1 2 1 occurs twice with overlap.
+    let reg = Regex::seq(&[1, 2, 1]).compile();
+    let ranges: Vec<_> = reg
+        .find_overlapping_iter(&[1, 2, 1, 2, 1, 3])
+        .map(|m| m.range())
+        .collect();
+    assert_eq!(ranges, vec![0..3, 2..5]);
+    assert_eq!(reg.find_iter(&[1, 2, 1, 2, 1, 3]).count(), 1);
+}
+
+#[test]
+fn find_overlapping_iter_one_match_per_start() {
+    let reg = Regex::repeat1(Regex::is(1), true).compile();
+    let ranges: Vec<_> = reg
+        .find_overlapping_iter(&[1, 1, 0, 1])
+        .map(|m| m.range())
+        .collect();
+    assert_eq!(ranges, vec![0..2, 1..2, 3..4]);
+}
+
+#[test]
+fn find_overlapping_iter_empty_match() {
+    let reg = Regex::repeat0(Regex::is(1), true).compile();
+    let ranges: Vec<_> = reg
+        .find_overlapping_iter(&[1, 0])
+        .map(|m| m.range())
+        .collect();
+    assert_eq!(ranges, vec![0..1, 1..1, 2..2]);
+}
+
+#[test]
+fn find_all_spans() {
+    for engine in ENGINES {
+        let reg = compile(&Regex::repeat1(Regex::is(1), false), engine);
+        let spans: Vec<_> = reg
+            .find_all_spans(&[1, 1, 0, 1])
+            .iter()
+            .map(|m| m.range())
+            .collect();
+        assert_eq!(spans, vec![0..1, 0..2, 1..2, 3..4], "{:?}", engine);
+    }
+}
+
+#[test]
+fn find_all_spans_with_empty_match() {
+    for engine in ENGINES {
+        let reg = compile(&Regex::repeat0(Regex::is(1), true), engine);
+        let spans: Vec<_> = reg
+            .find_all_spans(&[1, 0])
+            .iter()
+            .map(|m| m.range())
+            .collect();
+        assert_eq!(spans, vec![0..0, 0..1, 1..1, 2..2], "{:?}", engine);
+    }
+}
+
+#[test]
+fn find_all_spans_with_back_ref() {
+    // (.)\1
+    let reg = Regex::concat(Regex::group(Regex::any()), Regex::back_ref(1)).compile();
+    let spans: Vec<_> = reg
+        .find_all_spans(&[2, 2, 2])
+        .iter()
+        .map(|m| m.range())
+        .collect();
+    assert_eq!(spans, vec![0..2, 1..3]);
+}
@@ -0,0 +1,146 @@
+use vec_reg_common::{CompiledRegex, ParseErrorKind, PredicateRegistry, Regex};
+
+fn registry() -> PredicateRegistry<i32> {
+    let mut registry = PredicateRegistry::new();
+    registry.insert("is_fizz", |x: &i32| x % 3 == 0);
+    registry.insert("is_buzz", |x: &i32| x % 5 == 0);
+    registry.insert("is_one", |x: &i32| *x == 1);
+    registry.insert_pair("increasing", |a: &i32, b: &i32| b > a);
+    registry.set_eq(|a: &i32, b: &i32| a == b);
+    registry
+}
+
+fn parse(pattern: &str) -> Regex<i32> {
+    Regex::parse(pattern, &registry()).unwrap()
+}
+
+#[test]
+fn parse_satisfy() {
+    let reg = parse("[is_fizz][^is_buzz]").compile();
+    assert!(reg.is_full_match(&[3, 4]));
+    assert!(!reg.is_full_match(&[3, 5]));
+
+    let reg = parse(".[increasing]+").compile();
+    assert_eq!(reg.find(&[3, 1, 2, 3, 0]).unwrap().range(), 1..4);
+    assert_eq!(reg.find(&[3, 4, 5, 0]).unwrap().range(), 0..3);
+
+    let reg = parse("[^increasing]").compile();
+    assert_eq!(reg.find(&[3, 4, 2]).unwrap().range(), 2..3);
+}
+
+#[test]
+fn parse_quantifiers() {
+    let cases = [
+        ("[is_one]?", &[1, 1][..], 0..1),
+        ("[is_one]??", &[1, 1][..], 0..0),
+        ("[is_one]*", &[1, 1][..], 0..2),
+        ("[is_one]*?", &[1, 1][..], 0..0),
+        ("[is_one]+", &[1, 1][..], 0..2),
+        ("[is_one]+?", &[1, 1][..], 0..1),
+        ("[is_one]{2}", &[1, 1, 1][..], 0..2),
+        ("[is_one]{1,}", &[1, 1, 1][..], 0..3),
+        ("[is_one]{1,}?", &[1, 1, 1][..], 0..1),
+        ("[is_one]{1,2}", &[1, 1, 1][..], 0..2),
+        ("[is_one]{1,2}?", &[1, 1, 1][..], 0..1),
+    ];
+    for (pattern, input, range) in cases {
+        let reg = parse(pattern).compile();
+        assert_eq!(reg.find(input).unwrap().range(), range, "{}", pattern);
+    }
+}
+
+#[test]
+fn parse_groups() {
+    let reg = parse("([is_fizz])(?:[is_buzz])(?P<\"one\">[is_one])").compile();
+    let captures = reg.captures(&[2, 3, 5, 1]).unwrap();
+    assert_eq!(captures.len(), 3);
+    assert_eq!(captures.get(1).unwrap().range(), 1..2);
+    assert_eq!(captures.name("one").unwrap().range(), 3..4);
+}
+
+#[test]
+fn parse_alternation_and_anchors() {
+    let reg = parse("^([is_fizz]|[is_buzz])+$").compile();
+    assert!(reg.is_match(&[3, 5, 6]));
+    assert!(!reg.is_match(&[3, 5, 7]));
+}
+
+#[test]
+fn parse_look_around_and_back_ref() {
+    let reg = parse("[is_fizz](?=[is_buzz])").compile();
+    assert_eq!(reg.find(&[3, 1, 6, 10]).unwrap().range(), 2..3);
+    let reg = parse("(?<![is_one])[is_fizz]").compile();
+    assert_eq!(reg.find(&[1, 3, 6]).unwrap().range(), 2..3);
+    let reg = parse("(.)(?P=1)").compile();
+    assert_eq!(reg.find(&[1, 2, 2]).unwrap().range(), 1..3);
+    let reg = parse("(?P<\"x\">.)(?P=\"x\")").compile();
+    assert_eq!(reg.find(&[1, 2, 2]).unwrap().range(), 1..3);
+}
+
+#[test]
+fn parse_back_ref_without_eq() {
+    // Values need not be comparable unless the pattern has back references.
+    struct Point(i32, i32);
+    let mut registry = PredicateRegistry::new();
+    registry.insert("on_axis", |p: &Point| p.0 == 0 || p.1 == 0);
+    let reg = Regex::parse("[on_axis]+", &registry).unwrap().compile();
+    assert!(reg.is_full_match(&[Point(0, 1), Point(2, 0)]));
+
+    let error = Regex::parse("([on_axis])(?P=1)", &registry).unwrap_err();
+    assert_eq!(error.position(), 15);
+    assert_eq!(error.kind(), &ParseErrorKind::BackRefWithoutEq);
+
+    registry.set_eq(|a: &Point, b: &Point| a.0 == b.0 && a.1 == b.1);
+    let reg = Regex::parse("([on_axis])(?P=1)", &registry)
+        .unwrap()
+        .compile();
+    assert!(reg.is_full_match(&[Point(0, 1), Point(0, 1)]));
+    assert!(!reg.is_full_match(&[Point(0, 1), Point(0, 2)]));
+}
+
+#[test]
+fn parse_ignores_whitespace() {
+    let reg = parse(" ( [ is_fizz ] | [^ is_buzz ] ) { 2 , 3 } ").compile();
+    assert_eq!(reg.find(&[3, 1, 2, 5]).unwrap().range(), 0..3);
+}
+
+#[test]
+fn parse_errors() {
+    let cases = [
+        ("", 0, ParseErrorKind::UnexpectedEnd),
+        ("[is_fizz", 8, ParseErrorKind::UnexpectedEnd),
+        ("[is_fizz])", 9, ParseErrorKind::UnexpectedChar(')')),
+        ("[is_fizz]|", 10, ParseErrorKind::UnexpectedEnd),
+        ("([is_fizz]", 10, ParseErrorKind::UnexpectedEnd),
+        ("()", 1, ParseErrorKind::UnexpectedChar(')')),
+        ("[]", 1, ParseErrorKind::UnexpectedChar(']')),
+        ("[|x| x > 1]", 1, ParseErrorKind::UnexpectedChar('|')),
+        (
+            "[is_fizz][is_even]",
+            10,
+            ParseErrorKind::UnknownPredicate("is_even".to_owned()),
+        ),
+        (".{a}", 2, ParseErrorKind::UnexpectedChar('a')),
+        (
+            ".{99999999999999999999999}",
+            2,
+            ParseErrorKind::InvalidNumber("99999999999999999999999".to_owned()),
+        ),
+        ("(?X.)", 2, ParseErrorKind::UnexpectedChar('X')),
+        ("(?P<\"name.)", 11, ParseErrorKind::UnexpectedEnd),
+    ];
+    for (pattern, position, kind) in cases {
+        let error = Regex::parse(pattern, &registry()).unwrap_err();
+        assert_eq!(error.position(), position, "{}", pattern);
+        assert_eq!(error.kind(), &kind, "{}", pattern);
+    }
+}
+
+#[test]
+fn parse_error_display() {
+    let error = Regex::parse("[is_fizz][is_even]", &registry()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unknown predicate: is_even at position 10"
+    );
+}
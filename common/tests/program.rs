@@ -0,0 +1,82 @@
+use vec_reg_common::Regex;
+
+fn is_fizz() -> Regex<i32> {
+    Regex::satisfy_named("is_fizz", |x: &i32| x % 3 == 0)
+}
+
+#[test]
+fn program_listing() {
+    let reg = Regex::concat(
+        Regex::repeat0(Regex::named_group("fizz", is_fizz()), false),
+        Regex::concat(Regex::not_satisfy(|x: &i32| *x == 0), Regex::end()),
+    )
+    .compile();
+    assert_eq!(
+        reg.program_listing(),
+        "   0  save open 0
+   1  split 6, 2
+   2  save open 1 \"fizz\"
+   3  check [is_fizz]
+   4  save close 1 \"fizz\"
+   5  jmp 1
+   6  check [<fn>]
+   7  end
+   8  save close 0
+   9  match
+"
+    );
+}
+
+#[test]
+fn program_listing_look_around() {
+    let reg = Regex::concat(Regex::look_ahead(is_fizz()), Regex::any()).compile();
+    assert_eq!(
+        reg.program_listing(),
+        "   0  save open 0
+   1  look-ahead
+       0  check [is_fizz]
+       1  match
+   2  check .
+   3  save close 0
+   4  match
+"
+    );
+}
+
+#[test]
+fn to_dot() {
+    let reg = Regex::or(
+        is_fizz(),
+        Regex::satisfy_pair_named("\"inc\"", |a: &i32, b: &i32| b > a),
+    )
+    .compile();
+    let dot = reg.to_dot();
+    assert!(dot.starts_with("digraph program {\n"));
+    assert!(dot.ends_with("}\n"));
+    assert!(dot.contains("n1 -> n2 [label=\"1\"];"), "{}", dot);
+    assert!(
+        dot.contains("n2 [label=\"2: check [is_fizz]\"];"),
+        "{}",
+        dot
+    );
+    assert!(
+        dot.contains("[label=\"4: check pair [\\\"inc\\\"]\"];"),
+        "{}",
+        dot
+    );
+    assert!(dot.contains("shape=doublecircle"), "{}", dot);
+}
+
+#[test]
+fn to_dot_look_around() {
+    let reg = Regex::negative_look_behind(is_fizz()).compile();
+    let dot = reg.to_dot();
+    assert!(dot.contains("subgraph cluster_n1 {"), "{}", dot);
+    assert!(
+        dot.contains("n1_0 [label=\"0: check [is_fizz]\"];"),
+        "{}",
+        dot
+    );
+    assert!(dot.contains("n1 -> n1_0 [style=dashed];"), "{}", dot);
+    assert!(dot.contains("n1 -> n2;"), "{}", dot);
+}
@@ -0,0 +1,102 @@
+use vec_reg_common::{Anchored, CompileError, CompileOptions, Regex, RegexSet};
+
+fn patterns() -> Vec<Regex<i32>> {
+    vec![
+        // Three consecutive zeros.
+        Regex::repeat_n(Regex::is(0), 3),
+        // Negative value.
+        Regex::satisfy(|x: &i32| *x < 0),
+        // Increase followed by a decrease.
+        Regex::concat(
+            Regex::satisfy_pair(|a: &i32, b: &i32| b > a),
+            Regex::satisfy_pair(|a: &i32, b: &i32| b < a),
+        ),
+    ]
+}
+
+#[test]
+fn matches() {
+    let set = RegexSet::new(patterns());
+    assert_eq!(set.len(), 3);
+    let matches = set.matches(&[1, 0, 0, 0, 5, 2]);
+    assert!(matches.matched_any());
+    assert!(matches.matched(0));
+    assert!(!matches.matched(1));
+    assert!(matches.matched(2));
+    assert_eq!(matches.iter().collect::<Vec<_>>(), vec![0, 2]);
+
+    assert!(set.is_match(&[-1]));
+    assert!(!set.is_match(&[1, 2, 3]));
+}
+
+#[test]
+fn find_all() {
+    let set = RegexSet::new(patterns());
+    let found: Vec<_> = set
+        .find_all(&[1, 0, 0, 0, 5, 2, -1])
+        .iter()
+        .map(|m| m.as_ref().map(|m| m.range()))
+        .collect();
+    assert_eq!(found, vec![Some(1..4), Some(6..7), Some(4..6)]);
+}
+
+#[test]
+fn find_all_leftmost_first_per_pattern() {
+    let set = RegexSet::new(vec![
+        Regex::or(Regex::is(1), Regex::seq(&[1, 2])),
+        Regex::or(Regex::seq(&[1, 2]), Regex::is(1)),
+        Regex::repeat1(Regex::is(2), true),
+        Regex::repeat1(Regex::is(2), false),
+    ]);
+    let found: Vec<_> = set
+        .find_all(&[0, 1, 2, 2])
+        .iter()
+        .map(|m| m.as_ref().map(|m| m.range()))
+        .collect();
+    assert_eq!(found, vec![Some(1..2), Some(1..3), Some(2..4), Some(2..3)]);
+}
+
+#[test]
+fn empty_set() {
+    let set = RegexSet::<i32>::new(vec![]);
+    assert!(set.is_empty());
+    assert!(!set.is_match(&[1]));
+}
+
+#[test]
+fn anchored_set() {
+    let set = RegexSet::try_new(
+        vec![Regex::is(1), Regex::is(2)],
+        &CompileOptions::new().anchored(Anchored::Start),
+    )
+    .unwrap();
+    let matches = set.matches(&[2, 1]);
+    assert_eq!(matches.iter().collect::<Vec<_>>(), vec![1]);
+}
+
+#[test]
+fn back_ref_not_supported() {
+    let set = RegexSet::try_new(
+        vec![
+            Regex::is(1),
+            Regex::concat(Regex::group(Regex::is(1)), Regex::back_ref(1)),
+        ],
+        &CompileOptions::new(),
+    );
+    assert_eq!(set.err(), Some(CompileError::BackRefNotSupported));
+}
+
+#[test]
+fn size_limit_of_set() {
+    let set = RegexSet::try_new(
+        vec![
+            Regex::repeat_n(Regex::is(1), 6),
+            Regex::repeat_n(Regex::is(1), 6),
+        ],
+        &CompileOptions::new().size_limit(10),
+    );
+    assert!(matches!(
+        set.err(),
+        Some(CompileError::SizeLimitExceeded { limit: 10, .. })
+    ));
+}
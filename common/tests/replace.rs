@@ -0,0 +1,84 @@
+use vec_reg_common::{Captures, CompiledRegex, Regex, Template};
+
+#[test]
+fn replace_first() {
+    let reg = Regex::repeat1(Regex::is(0), true).compile();
+    let replaced = reg.replace(&[1, 0, 0, 2, 0, 3], |_: &Captures<i32>| vec![-1]);
+    assert_eq!(replaced, vec![1, -1, 2, 0, 3]);
+}
+
+#[test]
+fn replace_all() {
+    let reg = Regex::repeat1(Regex::is(0), true).compile();
+    let replaced = reg.replace_all(&[1, 0, 0, 2, 0, 3], |_: &Captures<i32>| vec![-1]);
+    assert_eq!(replaced, vec![1, -1, 2, -1, 3]);
+}
+
+#[test]
+fn replacen() {
+    let reg = Regex::is(0).compile();
+    let replaced = reg.replacen(&[0, 1, 0, 2, 0], 2, |_: &Captures<i32>| None);
+    assert_eq!(replaced, vec![1, 2, 0]);
+}
+
+#[test]
+fn replace_no_match() {
+    let reg = Regex::is(0).compile();
+    let replaced = reg.replace_all(&[1, 2, 3], |_: &Captures<i32>| vec![-1]);
+    assert_eq!(replaced, vec![1, 2, 3]);
+}
+
+#[test]
+fn replace_with_captures() {
+    let reg = Regex::concat(
+        Regex::group(Regex::satisfy(|x: &i32| x % 2 == 0)),
+        Regex::group(Regex::satisfy(|x: &i32| x % 2 == 1)),
+    )
+    .compile();
+    let replaced = reg.replace_all(&[2, 3, 9, 4, 5], |caps: &Captures<i32>| {
+        let even = caps.get(1).unwrap().values()[0];
+        let odd = caps.get(2).unwrap().values()[0];
+        vec![odd, even]
+    });
+    assert_eq!(replaced, vec![3, 2, 9, 5, 4]);
+}
+
+#[test]
+fn replace_empty_matches() {
+    let reg = Regex::repeat0(Regex::is(0), true).compile();
+    let replaced = reg.replace_all(&[1, 0, 2], |_: &Captures<i32>| vec![-1]);
+    assert_eq!(replaced, vec![-1, 1, -1, 2, -1]);
+}
+
+#[test]
+fn replace_with_template() {
+    let reg = Regex::concat(
+        Regex::named_group("fizz", Regex::satisfy(|x: &i32| x % 3 == 0)),
+        Regex::group(Regex::satisfy(|x: &i32| x % 5 == 0)),
+    )
+    .compile();
+    let template = Template::new().group(2).values([0, 0]).named_group("fizz");
+    assert_eq!(
+        reg.replace_all(&[1, 3, 5, 2, 6, 10], &template),
+        vec![1, 5, 0, 0, 3, 2, 10, 0, 0, 6]
+    );
+    assert_eq!(
+        reg.replace(&[1, 3, 5, 2, 6, 10], template),
+        vec![1, 5, 0, 0, 3, 2, 6, 10]
+    );
+}
+
+#[test]
+fn replace_with_template_missing_group() {
+    let reg = Regex::concat(
+        Regex::zero_or_one(Regex::group(Regex::is(1)), true),
+        Regex::is(2),
+    )
+    .compile();
+    let template = Template::new()
+        .value(0)
+        .group(1)
+        .group(5)
+        .named_group("unknown");
+    assert_eq!(reg.replace_all(&[2, 1, 2], template), vec![0, 0, 1]);
+}
@@ -0,0 +1,57 @@
+use vec_reg_common::{CompiledRegex, Regex};
+
+#[test]
+fn strictly_increasing_run() {
+    let reg = Regex::concat(
+        Regex::any(),
+        Regex::repeat1(
+            Regex::satisfy_pair(|prev: &i32, cur: &i32| cur > prev),
+            true,
+        ),
+    )
+    .compile();
+    assert_eq!(reg.find(&[5, 3, 4, 7, 7, 8]).unwrap().range(), 1..4);
+    assert!(reg.is_full_match(&[1, 2, 3]));
+    assert!(!reg.is_match(&[3, 2, 1]));
+}
+
+#[test]
+fn first_value_has_no_previous() {
+    let reg = Regex::satisfy_pair(|_: &i32, _: &i32| true).compile();
+    assert!(!reg.is_match(&[1]));
+    assert_eq!(reg.find(&[1, 2]).unwrap().range(), 1..2);
+}
+
+#[test]
+fn previous_value_before_start_offset() {
+    let reg = Regex::satisfy_pair(|prev: &i32, cur: &i32| cur > prev).compile();
+    // Previous value is taken from the whole input, even before the start offset.
+    assert_eq!(reg.find_at(&[1, 2], 1).unwrap().range(), 1..2);
+}
+
+#[test]
+fn relative_change() {
+    // Value differs from previous by more than 10%.
+    let jump = |prev: &f64, cur: &f64| ((cur - prev) / prev).abs() > 0.1;
+    let reg = Regex::satisfy_pair(jump).compile();
+    assert_eq!(reg.find(&[100.0, 105.0, 120.0]).unwrap().range(), 2..3);
+    assert!(!reg.is_match(&[100.0, 105.0, 110.0]));
+}
+
+#[test]
+fn not_satisfy_pair() {
+    let reg = Regex::not_satisfy_pair(|prev: &i32, cur: &i32| cur > prev).compile();
+    assert_eq!(reg.find(&[1, 2, 2]).unwrap().range(), 2..3);
+    assert!(!reg.is_match(&[1]));
+}
+
+#[test]
+fn satisfy_pair_in_look_behind() {
+    // A value preceded by an increase.
+    let reg = Regex::concat(
+        Regex::look_behind(Regex::satisfy_pair(|prev: &i32, cur: &i32| cur > prev)),
+        Regex::any(),
+    )
+    .compile();
+    assert_eq!(reg.find(&[3, 2, 5, 0]).unwrap().range(), 3..4);
+}
@@ -0,0 +1,47 @@
+use vec_reg_common::{CompiledRegex, Regex};
+
+fn three_zeros() -> Regex<i32> {
+    Regex::repeat_n(Regex::is(0), 3)
+}
+
+#[test]
+fn split() {
+    let reg = three_zeros().compile();
+    let input = [1, 2, 0, 0, 0, 3, 0, 4, 0, 0, 0, 5];
+    let pieces: Vec<_> = reg.split(&input).collect();
+    assert_eq!(pieces, vec![&[1, 2][..], &[3, 0, 4], &[5]]);
+}
+
+#[test]
+fn split_no_match() {
+    let reg = three_zeros().compile();
+    let pieces: Vec<_> = reg.split(&[1, 2, 3]).collect();
+    assert_eq!(pieces, vec![&[1, 2, 3][..]]);
+}
+
+#[test]
+fn split_empty_input() {
+    let reg = three_zeros().compile();
+    let pieces: Vec<_> = reg.split(&[]).collect();
+    assert_eq!(pieces, vec![&[][..]]);
+}
+
+#[test]
+fn split_leading_and_trailing_match() {
+    let reg = three_zeros().compile();
+    let pieces: Vec<_> = reg.split(&[0, 0, 0, 1, 0, 0, 0]).collect();
+    assert_eq!(pieces, vec![&[][..], &[1], &[]]);
+}
+
+#[test]
+fn splitn() {
+    let reg = Regex::is(0).compile();
+    let input = [1, 0, 2, 0, 3, 0, 4];
+    let pieces: Vec<_> = reg.splitn(&input, 2).collect();
+    assert_eq!(pieces, vec![&[1][..], &[2, 0, 3, 0, 4]]);
+
+    assert_eq!(reg.splitn(&input, 0).count(), 0);
+
+    let pieces: Vec<_> = reg.splitn(&input, 10).collect();
+    assert_eq!(pieces, vec![&[1][..], &[2], &[3], &[4]]);
+}
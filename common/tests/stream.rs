@@ -0,0 +1,143 @@
+use vec_reg_common::{
+    Anchored, CompileError, CompileOptions, CompiledRegex, MatchKind, Regex, StreamMatcher,
+};
+
+fn stream_ranges(
+    reg: &vec_reg_common::CompiledRegexInVm<i32>,
+    input: &[i32],
+) -> Vec<(usize, usize)> {
+    let mut matcher = StreamMatcher::new(reg).unwrap();
+    let mut ranges = vec![];
+    for value in input {
+        if let Some(m) = matcher.feed(value) {
+            ranges.push((m.start(), m.end()));
+        }
+    }
+    ranges.extend(matcher.finish().iter().map(|m| (m.start(), m.end())));
+    ranges
+}
+
+fn find_ranges(reg: &vec_reg_common::CompiledRegexInVm<i32>, input: &[i32]) -> Vec<(usize, usize)> {
+    reg.find_iter(input).map(|m| (m.start(), m.end())).collect()
+}
+
+#[test]
+fn stream_matches_same_as_find_iter() {
+    let regs = vec![
+        Regex::repeat1(Regex::is(1), true),
+        Regex::repeat1(Regex::is(1), false),
+        Regex::repeat0(Regex::is(1), true),
+        Regex::or(Regex::seq(&[1, 2, 3]), Regex::is(1)),
+        Regex::concat(Regex::is(1), Regex::end()),
+        Regex::concat(Regex::begin(), Regex::is(1)),
+        Regex::concat(Regex::zero_or_one(Regex::is(2), true), Regex::end()),
+        Regex::group(Regex::repeat_min_max(Regex::is(1), 1, 2, false)),
+    ];
+    let inputs: Vec<Vec<i32>> = vec![
+        vec![],
+        vec![1],
+        vec![1, 1, 0, 1],
+        vec![1, 2, 1, 2, 3, 1],
+        vec![0, 1, 1, 1, 2],
+        vec![2, 1, 2],
+    ];
+    for reg in regs {
+        let reg = reg.compile();
+        for input in &inputs {
+            assert_eq!(
+                stream_ranges(&reg, input),
+                find_ranges(&reg, input),
+                "{:?}",
+                input
+            );
+        }
+    }
+}
+
+#[test]
+fn stream_reports_match_once_decided() {
+    let reg = Regex::or(Regex::seq(&[1, 2, 3]), Regex::is(1)).compile();
+    let mut matcher = StreamMatcher::new(&reg).unwrap();
+    assert_eq!(matcher.feed(&1), None);
+    assert_eq!(matcher.feed(&2), None);
+    // 1 2 3 fails, so the match 0..1 is decided, and the search resumes from 1.
+    assert_eq!(matcher.feed(&1).map(|m| m.range()), Some(0..1));
+    assert_eq!(matcher.feed(&1).map(|m| m.range()), Some(2..3));
+    let rest: Vec<_> = matcher.finish().iter().map(|m| m.range()).collect();
+    assert_eq!(rest, vec![3..4]);
+}
+
+#[test]
+fn stream_end_of_input_at_finish() {
+    let reg = Regex::concat(Regex::is(1), Regex::end()).compile();
+    let mut matcher = StreamMatcher::new(&reg).unwrap();
+    assert_eq!(matcher.feed(&1), None);
+    assert_eq!(matcher.feed(&1), None);
+    let rest: Vec<_> = matcher.finish().iter().map(|m| m.range()).collect();
+    assert_eq!(rest, vec![1..2]);
+}
+
+#[test]
+fn stream_pair_predicate_across_feeds() {
+    let reg = Regex::repeat1(Regex::satisfy_pair(|a: &i32, b: &i32| b > a), true).compile();
+    let input = [1, 2, 3, 1, 5, 0];
+    let mut matcher = StreamMatcher::new(&reg).unwrap();
+    let mut ranges = vec![];
+    for value in input {
+        ranges.extend(matcher.feed(&value).map(|m| m.range()));
+    }
+    ranges.extend(matcher.finish().iter().map(|m| m.range()));
+    assert_eq!(ranges, vec![1..3, 4..5]);
+}
+
+#[test]
+fn stream_global_offsets() {
+    let reg = Regex::seq(&[7, 8]).compile();
+    let mut matcher = StreamMatcher::new(&reg).unwrap();
+    let mut ranges = vec![];
+    for i in 0..1000 {
+        ranges.extend(matcher.feed(&(i % 10)).map(|m| m.range()));
+    }
+    assert!(matcher.finish().is_empty());
+    assert_eq!(ranges.len(), 100);
+    assert_eq!(ranges[99], 997..999);
+}
+
+#[test]
+fn stream_anchored() {
+    let reg = Regex::is(1)
+        .try_compile_with(&CompileOptions::new().anchored(Anchored::Start))
+        .unwrap();
+    assert_eq!(stream_ranges(&reg, &[1, 1, 0, 1]), vec![(0, 1), (1, 2)]);
+    assert_eq!(
+        stream_ranges(&reg, &[1, 1, 0, 1]),
+        find_ranges(&reg, &[1, 1, 0, 1])
+    );
+
+    let reg = Regex::repeat1(Regex::is(1), true)
+        .try_compile_with(&CompileOptions::new().anchored(Anchored::Both))
+        .unwrap();
+    assert_eq!(stream_ranges(&reg, &[1, 1, 1]), vec![(0, 3)]);
+    assert_eq!(stream_ranges(&reg, &[1, 0, 1]), vec![]);
+}
+
+#[test]
+fn stream_rejects_unsupported_regex() {
+    let reg = Regex::look_ahead(Regex::is(1)).compile();
+    assert_eq!(
+        StreamMatcher::new(&reg).err(),
+        Some(CompileError::NotStreamable)
+    );
+    let reg = Regex::concat(Regex::group(Regex::<i32>::any()), Regex::back_ref(1)).compile();
+    assert_eq!(
+        StreamMatcher::new(&reg).err(),
+        Some(CompileError::NotStreamable)
+    );
+    let reg = Regex::is(1)
+        .try_compile_with(&CompileOptions::new().match_kind(MatchKind::LeftmostLongest))
+        .unwrap();
+    assert_eq!(
+        StreamMatcher::new(&reg).err(),
+        Some(CompileError::NotStreamable)
+    );
+}
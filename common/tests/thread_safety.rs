@@ -0,0 +1,41 @@
+use std::sync::OnceLock;
+
+use vec_reg_common::{CompiledRegex, Regex};
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn regex_is_send_sync() {
+    let reg = Regex::repeat1(Regex::satisfy(|x: &i32| x % 2 == 0), true);
+    assert_send_sync(&reg);
+    assert_send_sync(&reg.compile());
+}
+
+#[test]
+fn captures_is_send_sync() {
+    let reg = Regex::named_group("even", Regex::satisfy(|x: &i32| x % 2 == 0)).compile();
+    let captures = reg.captures(&[1, 2]).unwrap();
+    assert_send_sync(&captures);
+}
+
+#[test]
+fn share_compiled_regex_between_threads() {
+    let reg = Regex::repeat1(Regex::satisfy(|x: &i32| x % 2 == 0), true).compile();
+    let inputs = [vec![1, 2, 4], vec![1, 3, 5], vec![6]];
+    let results: Vec<bool> = std::thread::scope(|s| {
+        let handles: Vec<_> = inputs
+            .iter()
+            .map(|input| s.spawn(|| reg.is_match(input)))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(results, vec![true, false, true]);
+}
+
+#[test]
+fn compiled_regex_in_static() {
+    static IS_EVEN: OnceLock<Box<dyn CompiledRegex<i32> + Send + Sync>> = OnceLock::new();
+    let reg = IS_EVEN.get_or_init(|| Box::new(Regex::satisfy(|x: &i32| x % 2 == 0).compile()));
+    assert!(reg.is_match(&[1, 2]));
+    assert!(!reg.is_match(&[1, 3]));
+}
@@ -0,0 +1,87 @@
+use vec_reg_common::{Anchored, CompileError, CompileOptions, Regex};
+
+fn fizz_buzz_buzz() -> Regex<i32> {
+    let is_fizz = Regex::satisfy_named("is_fizz", |x: &i32| x % 3 == 0);
+    let is_buzz = Regex::satisfy_named("is_buzz", |x: &i32| x % 5 == 0);
+    Regex::concat(is_fizz, Regex::repeat_n(is_buzz, 2))
+}
+
+#[test]
+fn trace_no_match() {
+    let reg = fizz_buzz_buzz().compile();
+    let trace = reg.trace(&[3, 5, 7, 1]).unwrap();
+    assert_eq!(trace.matched(), None);
+    assert_eq!(trace.furthest(), Some(0..2));
+
+    let steps = trace.steps();
+    assert_eq!(steps.len(), 5);
+    let step = &steps[2];
+    assert_eq!(step.position(), 2);
+    let locations: Vec<_> = step.threads().iter().map(|t| t.location()).collect();
+    assert_eq!(locations, vec!["[is_buzz]", "[is_fizz]"]);
+    assert_eq!(step.threads()[0].start(), 0);
+    assert_eq!(step.threads()[1].start(), 2);
+    let results: Vec<_> = step
+        .tests()
+        .iter()
+        .map(|t| (t.location(), t.result()))
+        .collect();
+    assert_eq!(results, vec![("[is_buzz]", false), ("[is_fizz]", false)]);
+
+    // No test at the end of input.
+    assert!(steps[4].tests().is_empty());
+}
+
+#[test]
+fn trace_match() {
+    let reg = fizz_buzz_buzz().compile();
+    let trace = reg.trace(&[1, 3, 5, 10, 3]).unwrap();
+    assert_eq!(trace.matched(), Some(1..4));
+    assert_eq!(trace.furthest(), Some(1..4));
+    // The search stops once the match is decided.
+    assert_eq!(trace.steps().last().unwrap().position(), 4);
+    let matched = &trace.steps()[4].threads()[0];
+    assert_eq!(matched.location(), "Match");
+}
+
+#[test]
+fn trace_anchored() {
+    let reg = fizz_buzz_buzz()
+        .try_compile_with(&CompileOptions::new().anchored(Anchored::Start))
+        .unwrap();
+    let trace = reg.trace(&[1, 3, 5, 10]).unwrap();
+    assert_eq!(trace.matched(), None);
+    assert_eq!(trace.furthest(), None);
+    assert_eq!(trace.steps().len(), 1);
+}
+
+#[test]
+fn trace_report() {
+    let reg = Regex::concat(
+        Regex::satisfy_named("is_one", |x: &i32| *x == 1),
+        Regex::any(),
+    )
+    .compile();
+    let report = reg.trace(&[2, 1]).unwrap().to_string();
+    assert_eq!(
+        report,
+        "position 0:
+  pc 1 [is_one] (from 0): failed
+position 1:
+  pc 1 [is_one] (from 1): ok
+position 2:
+  pc 2 . (from 1)
+  pc 1 [is_one] (from 2)
+furthest: position 2 from 1
+no match"
+    );
+}
+
+#[test]
+fn trace_back_ref_not_supported() {
+    let reg = Regex::concat(Regex::group(Regex::<i32>::any()), Regex::back_ref(1)).compile();
+    assert_eq!(
+        reg.trace(&[1, 1]).err(),
+        Some(CompileError::BackRefNotSupported)
+    );
+}
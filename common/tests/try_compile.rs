@@ -0,0 +1,106 @@
+use vec_reg_common::{CompileError, CompileOptions, CompiledRegex, Regex};
+
+#[test]
+fn valid_regex() {
+    let reg = Regex::repeat_min_max(Regex::is(1), 1, 2, true).try_compile();
+    assert!(reg.is_ok());
+    assert!(reg.unwrap().is_full_match(&[1, 1]));
+}
+
+#[test]
+fn invalid_repetition() {
+    let reg = Regex::repeat_min_max(Regex::is(1), 3, 2, true).try_compile();
+    assert_eq!(
+        reg.err(),
+        Some(CompileError::InvalidRepetition { min: 3, max: 2 })
+    );
+}
+
+#[test]
+fn duplicate_group_name() {
+    let reg = Regex::concat(
+        Regex::named_group("a", Regex::is(1)),
+        Regex::group(Regex::named_group("a", Regex::is(2))),
+    )
+    .try_compile();
+    assert_eq!(
+        reg.err(),
+        Some(CompileError::DuplicateGroupName("a".to_owned()))
+    );
+}
+
+#[test]
+fn repeated_named_group_is_not_duplicate() {
+    let reg = Regex::repeat_n(Regex::named_group("a", Regex::is(1)), 2).try_compile();
+    assert!(reg.is_ok());
+}
+
+#[test]
+fn size_limit_exceeded() {
+    let reg = Regex::repeat_n(Regex::is(1), usize::MAX).try_compile();
+    assert!(matches!(
+        reg.err(),
+        Some(CompileError::SizeLimitExceeded { .. })
+    ));
+
+    let options = CompileOptions::new().size_limit(20);
+    let reg = Regex::repeat_n(Regex::is(1), 10).try_compile_with(&options);
+    assert!(reg.is_ok());
+    let reg = Regex::repeat_n(Regex::is(1), 20).try_compile_with(&options);
+    assert!(matches!(
+        reg.err(),
+        Some(CompileError::SizeLimitExceeded { limit: 20, .. })
+    ));
+}
+
+#[test]
+fn zero_or_more_repetition() {
+    let reg = Regex::repeat_n_or_more(Regex::is(1), 0, true)
+        .try_compile()
+        .unwrap();
+    assert!(reg.is_full_match(&[]));
+    assert!(reg.is_full_match(&[1, 1, 1]));
+    assert!(!reg.is_full_match(&[1, 2]));
+}
+
+#[test]
+fn zero_repetition() {
+    let reg = Regex::concat(Regex::is(1), Regex::repeat_n(Regex::is(2), 0))
+        .try_compile()
+        .unwrap();
+    assert!(reg.is_full_match(&[1]));
+    assert!(!reg.is_full_match(&[1, 2]));
+
+    let reg = Regex::concat(
+        Regex::is(1),
+        Regex::repeat_min_max(Regex::is(2), 0, 0, true),
+    )
+    .try_compile()
+    .unwrap();
+    assert!(reg.is_full_match(&[1]));
+    assert!(!reg.is_full_match(&[1, 2]));
+}
+
+#[test]
+fn zero_to_m_repetition() {
+    let reg = Regex::repeat_min_max(Regex::is(1), 0, 2, true)
+        .try_compile()
+        .unwrap();
+    assert!(reg.is_full_match(&[]));
+    assert!(reg.is_full_match(&[1, 1]));
+    assert!(!reg.is_full_match(&[1, 1, 1]));
+}
+
+#[test]
+#[should_panic(expected = "invalid repetition")]
+fn compile_panics_on_invalid_regex() {
+    Regex::repeat_min_max(Regex::is(1), 3, 2, true).compile();
+}
+
+#[test]
+fn error_display() {
+    assert_eq!(
+        CompileError::DuplicateGroupName("a".to_owned()).to_string(),
+        "duplicate capture group name: a"
+    );
+}
@@ -15,7 +15,9 @@ impl RegexMacroInput {
         Ok(syn::parse_quote!(vec_reg_common::Regex::any()))
     }
 
-    // parse [#<ident>] or [#<closure>] syntax to Regex::statisfy
+    // parse [#<ident>] or [#<closure>] syntax to Regex::statisfy_named,
+    // or [#<closure with two arguments>] syntax to Regex::satisfy_pair_named.
+    // The predicate is labeled by the function name or the tokens of the closure.
     fn parse_satisfy(input: ParseStream) -> Result<proc_macro2::TokenStream> {
         if !input.peek(syn::token::Bracket) {
             return Err(syn::Error::new(input.span(), "expected brace"));
@@ -25,16 +27,28 @@ impl RegexMacroInput {
         bracketed!(braced_content in input);
         let inverse = braced_content.parse::<syn::Token![^]>().is_ok();
         if let Ok(fn_name) = braced_content.parse::<syn::Ident>() {
+            let label = fn_name.to_string();
             if inverse {
-                Ok(syn::parse_quote!(vec_reg_common::Regex::not_satisfy(#fn_name)))
+                Ok(syn::parse_quote!(vec_reg_common::Regex::not_satisfy_named(#label, #fn_name)))
             } else {
-                Ok(syn::parse_quote!(vec_reg_common::Regex::satisfy(#fn_name)))
+                Ok(syn::parse_quote!(vec_reg_common::Regex::satisfy_named(#label, #fn_name)))
             }
         } else if let Ok(closure) = braced_content.parse::<syn::ExprClosure>() {
-            if inverse {
-                Ok(syn::parse_quote!(vec_reg_common::Regex::not_satisfy(#closure)))
+            let label = quote::quote!(#closure).to_string();
+            if closure.inputs.len() == 2 {
+                if inverse {
+                    Ok(
+                        syn::parse_quote!(vec_reg_common::Regex::not_satisfy_pair_named(#label, #closure)),
+                    )
+                } else {
+                    Ok(
+                        syn::parse_quote!(vec_reg_common::Regex::satisfy_pair_named(#label, #closure)),
+                    )
+                }
+            } else if inverse {
+                Ok(syn::parse_quote!(vec_reg_common::Regex::not_satisfy_named(#label, #closure)))
             } else {
-                Ok(syn::parse_quote!(vec_reg_common::Regex::satisfy(#closure)))
+                Ok(syn::parse_quote!(vec_reg_common::Regex::satisfy_named(#label, #closure)))
             }
         } else {
             Err(syn::Error::new(
@@ -87,6 +101,27 @@ impl RegexMacroInput {
         }
     }
 
+    // parse n or "name" in (?P=n), (?P="name") syntax to Regex::back_ref, Regex::named_back_ref
+    fn parse_back_ref(input: ParseStream) -> Result<proc_macro2::TokenStream> {
+        let back_ref = if input.peek(syn::LitInt) {
+            let group_index = input.parse::<syn::LitInt>()?;
+            syn::parse_quote!(vec_reg_common::Regex::back_ref(#group_index))
+        } else if input.peek(syn::LitStr) {
+            let group_name = input.parse::<syn::LitStr>()?;
+            syn::parse_quote!(vec_reg_common::Regex::named_back_ref(#group_name))
+        } else {
+            return Err(syn::Error::new(
+                input.span(),
+                "expected integer literal or string literal",
+            ));
+        };
+        if !input.is_empty() {
+            return Err(syn::Error::new(input.span(), "unexpected tokens"));
+        }
+
+        Ok(back_ref)
+    }
+
     fn parse_atom(input: ParseStream) -> Result<proc_macro2::TokenStream> {
         if let Ok(any_regex) = Self::parse_any(input) {
             Ok(any_regex)
@@ -97,14 +132,28 @@ impl RegexMacroInput {
             parenthesized!(parend_content in input);
             let mut capturing = true;
             let mut name = None;
+            let mut look_around = None;
             if parend_content.parse::<syn::token::Question>().is_ok() {
                 if parend_content.parse::<syn::token::Colon>().is_ok() {
                     capturing = false;
+                } else if parend_content.parse::<syn::Token![=]>().is_ok() {
+                    look_around = Some(quote::quote!(look_ahead));
+                } else if parend_content.parse::<syn::Token![!]>().is_ok() {
+                    look_around = Some(quote::quote!(negative_look_ahead));
+                } else if parend_content.parse::<syn::Token![<=]>().is_ok() {
+                    look_around = Some(quote::quote!(look_behind));
+                } else if parend_content.peek(syn::Token![<]) {
+                    parend_content.parse::<syn::Token![<]>()?;
+                    parend_content.parse::<syn::Token![!]>()?;
+                    look_around = Some(quote::quote!(negative_look_behind));
                 } else if parend_content.peek(syn::Ident) {
                     let parsed_ident = parend_content.parse::<syn::Ident>()?;
                     if parsed_ident.to_string().as_str() != "P" {
                         return Err(syn::Error::new(parsed_ident.span(), "expected 'P'"));
                     }
+                    if parend_content.parse::<syn::Token![=]>().is_ok() {
+                        return Self::parse_back_ref(&parend_content);
+                    }
                     if let Err(err) = parend_content.parse::<syn::Token![<]>() {
                         return Err(err);
                     }
@@ -121,7 +170,9 @@ impl RegexMacroInput {
             }
             match Self::parse_expr(&parend_content) {
                 Ok(expr) => {
-                    if capturing && name.is_none() {
+                    if let Some(look_around) = look_around {
+                        Ok(syn::parse_quote!(vec_reg_common::Regex::#look_around(#expr)))
+                    } else if capturing && name.is_none() {
                         Ok(syn::parse_quote!(vec_reg_common::Regex::group(#expr)))
                     } else if capturing && name.is_some() {
                         let name = name.unwrap();
@@ -135,7 +186,7 @@ impl RegexMacroInput {
         } else {
             Err(syn::Error::new(
                 input.span(),
-                "expected '.', {#<ident>}, {#<closure>}, (#<regex>), (?:#<regex>) or (?P<\"name\">#<regex>)",
+                "expected '.', {#<ident>}, {#<closure>}, (#<regex>), (?:#<regex>), (?P<\"name\">#<regex>), (?=#<regex>), (?!#<regex>), (?<=#<regex>) or (?<!#<regex>)",
             ))
         }
     }
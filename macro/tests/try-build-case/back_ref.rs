@@ -0,0 +1,6 @@
+use vec_reg_macro::vec_reg;
+
+fn main() {
+    vec_reg!(([|x: &i32| *x > 0]+)[|x: &i32| *x == 0](?P=1));
+    vec_reg!((?P<"id">[|x: &i32| *x > 0]+)[|x: &i32| *x == 0](?P="id"));
+}
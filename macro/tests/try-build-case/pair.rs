@@ -0,0 +1,6 @@
+use vec_reg_macro::vec_reg;
+
+fn main() {
+    vec_reg!([|x: &i32| *x > 0][|a: &i32, b: &i32| b > a]+);
+    vec_reg!(.[^|a: &i32, b: &i32| b > a]);
+}
@@ -14,4 +14,7 @@ fn test_builds() {
     t.pass("tests/try-build-case/non-greedy.rs");
     t.pass("tests/try-build-case/begin.rs");
     t.pass("tests/try-build-case/end.rs");
+    t.pass("tests/try-build-case/look_around.rs");
+    t.pass("tests/try-build-case/back_ref.rs");
+    t.pass("tests/try-build-case/pair.rs");
 }
@@ -20,12 +20,19 @@
 //! | <code>[\|x\| *x == 1]</code> | Match any values that satisfied given closure. |
 //! | `[^function_name]` | Match any values that not satisfied given function. |
 //! | <code>[\^\|x\| *x == 1]</code> | Match any values that not satisfied given closure. |
+//! | <code>[\|a, b\| b > a]</code> | Match any values that satisfied given closure together with the previous value. |
 //! | `.` | Match any values. |
 //! | `^` | a beginning of input |
 //! | `$` | a end of input |
 //! | `(R)` | numbered capturing group (submatch) |
 //! | `(?:R)` | non-capturing group |
 //! | `(?P<"name">R)` | named & numbered capturing group (submatch) |
+//! | `(?=R)` | look-ahead, `R` matches at the current position without consuming |
+//! | `(?!R)` | negative look-ahead, `R` does not match at the current position |
+//! | `(?<=R)` | look-behind, `R` matches ending at the current position |
+//! | `(?<!R)` | negative look-behind, `R` does not match ending at the current position |
+//! | `(?P=n)` | back reference, the same values captured by `n`th group |
+//! | `(?P="name")` | back reference, the same values captured by the named group |
 //! | `RS` | `R` followed by `S` |
 //! | <code>R\|S</code> | `R` or `S` (prefer `R`) |
 //! | `R?` | zero or one `R`, prefer one |
@@ -41,7 +48,13 @@
 //! | `R{n}` | exactly `n` `R` |
 //! | `R{n}?` | exactly `n` `R` |
 
-pub use vec_reg_common::{Captures, CompiledRegex, Match, Regex};
+pub use vec_reg_common::{
+    Anchored, CaptureMatches, Captures, CompileError, CompileOptions, CompiledRegex,
+    CompiledRegexInVm, Engine, EqFn, IterMatches, Match, MatchError, MatchEvent, MatchKind,
+    MatchTrace, Matches, OverlappingMatches, PairFn, ParseError, ParseErrorKind, PredicateRegistry,
+    PredicateTest, Regex, RegexSet, Replacer, SetMatches, Split, SplitN, StreamMatcher, Template,
+    TraceStep, TraceThread,
+};
 pub use vec_reg_macro::vec_reg;
 
 #[cfg(doctest)]
@@ -0,0 +1,17 @@
+use vec_reg::{vec_reg, CompiledRegex};
+
+#[test]
+fn back_ref() {
+    let is_id = |x: &i32| *x > 0;
+    let reg = vec_reg!(([is_id]+)[|x| *x == 0](?P=1)).compile();
+    assert!(reg.is_full_match(&[1, 2, 0, 1, 2]));
+    assert!(!reg.is_full_match(&[1, 2, 0, 2, 1]));
+}
+
+#[test]
+fn named_back_ref() {
+    let is_id = |x: &i32| *x > 0;
+    let reg = vec_reg!((?P<"id">[is_id]+)[|x| *x == 0](?P="id")).compile();
+    assert!(reg.is_full_match(&[3, 0, 3]));
+    assert!(!reg.is_full_match(&[3, 0, 4]));
+}
@@ -0,0 +1,21 @@
+use vec_reg::vec_reg;
+
+fn is_fizz(x: &i32) -> bool {
+    x % 3 == 0
+}
+
+#[test]
+fn macro_labels_function_name() {
+    let reg = vec_reg!([is_fizz]+[^is_fizz]);
+    assert_eq!(reg.to_string(), "[is_fizz]+[^is_fizz]");
+}
+
+#[test]
+fn macro_labels_closure_tokens() {
+    // Spacing of the tokens depends on the compiler, so compare without whitespaces.
+    let reg = vec_reg!(.[|x: &i32| *x == 1]|[|a, b| b > a]);
+    let display: String = reg.to_string().split_whitespace().collect();
+    assert_eq!(display, ".[|x:&i32|*x==1]|[|a,b|b>a]");
+    let dump: String = format!("{:?}", reg.compile()).split_whitespace().collect();
+    assert!(dump.contains("CheckPair(\"|a,b|b>a\")"), "{}", dump);
+}
@@ -0,0 +1,23 @@
+use vec_reg::{vec_reg, CompiledRegex};
+
+#[test]
+fn look_ahead() {
+    let is_spike = |x: &i32| *x >= 10;
+    let is_drop = |x: &i32| *x < 0;
+    let reg = vec_reg!([is_spike](?=[is_drop])).compile();
+    assert_eq!(reg.find(&[10, 2, 12, -1]).unwrap().range(), 2..3);
+
+    let reg = vec_reg!([is_spike](?![is_drop])).compile();
+    assert_eq!(reg.find(&[10, -1, 12, 2]).unwrap().range(), 2..3);
+}
+
+#[test]
+fn look_behind() {
+    let is_spike = |x: &i32| *x >= 10;
+    let is_drop = |x: &i32| *x < 0;
+    let reg = vec_reg!((?<=[is_spike])[is_drop]).compile();
+    assert_eq!(reg.find(&[-1, 10, -2]).unwrap().range(), 2..3);
+
+    let reg = vec_reg!((?<![is_spike])[is_drop]).compile();
+    assert_eq!(reg.find(&[10, -1, 2, -2]).unwrap().range(), 3..4);
+}
@@ -0,0 +1,13 @@
+use vec_reg::{vec_reg, CompiledRegex};
+
+#[test]
+fn strictly_increasing_run() {
+    let reg = vec_reg!(.[|a, b| b > a]+).compile();
+    assert_eq!(reg.find(&[5, 3, 4, 7, 7, 8]).unwrap().range(), 1..4);
+}
+
+#[test]
+fn not_increasing() {
+    let reg = vec_reg!(.[^|a, b| b > a]).compile();
+    assert_eq!(reg.find(&[1, 2, 2]).unwrap().range(), 1..3);
+}